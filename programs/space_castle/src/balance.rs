@@ -0,0 +1,260 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    battle::{Defenses, FleetStats, Weapons, MAX_ROUNDS},
+    building::BuildingType,
+    fleet::{HullClass, ShipModuleType},
+    planet::{
+        BASE_PRODUCTION, COLONIZATION_BASE_COST, COLONIZATION_BASE_COST_IGT,
+        COLONIZATION_COST_FACTOR, IGT_PRODUCTION, MAX_PRODUCTION_SLOTS, PRODUCTION_PER_LEVEL,
+        PRODUCTION_TICK_SLOTS,
+    },
+    resource::Resources,
+};
+
+/// Number of `BuildingType` variants
+pub const BUILDING_TYPES: usize = 12;
+/// Number of `HullClass` variants
+pub const HULL_CLASSES: usize = 4;
+/// Number of `ShipModuleType` variants
+pub const SHIP_MODULE_TYPES: usize = 11;
+/// Upper bound of the percent values that stats are scaled by
+pub const MAX_BONUS_PERCENT: u64 = 10_000;
+/// Upper bound of the per tick production values, keeps a day of production within u64
+pub const MAX_TICK_PRODUCTION: u64 = 1_000_000;
+/// Upper bound of the slot spans, about a year of slots
+pub const MAX_SLOTS: u64 = 80_000_000;
+/// Upper bound of the resources a `HaulingBay` carries per level
+pub const MAX_HAULING_BAY_CAPACITY: u64 = 1_000_000;
+
+#[account]
+#[derive(InitSpace)]
+/// Balance values read by the game formulas at runtime, so the world can be rebalanced
+/// without redeploying the program
+pub struct BalanceConfig {
+    /// Increased on every update
+    pub version: u32,
+    pub values: BalanceValues,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone)]
+#[cfg_attr(feature = "simulator", derive(serde::Serialize, serde::Deserialize))]
+pub struct BalanceValues {
+    /// Buildings get this much more expensive each level
+    pub building_upgrade_factor: f32,
+    /// Base upgrade cost indexed by `BuildingType`: [metal, crystal, chemical, fuel]
+    pub building_base_costs: [[f32; 4]; BUILDING_TYPES],
+    /// Base cost indexed by `HullClass`: [metal, crystal, chemical, fuel]
+    pub hull_base_costs: [[f32; 4]; HULL_CLASSES],
+    /// Ship modules get this much more expensive each level
+    pub module_upgrade_factor: f32,
+    /// Base cost indexed by `ShipModuleType`: [metal, crystal, chemical, fuel]
+    pub module_base_costs: [[f32; 4]; SHIP_MODULE_TYPES],
+    /// Base IGT cost indexed by `ShipModuleType`
+    pub module_base_costs_igt: [f32; SHIP_MODULE_TYPES],
+    /// Slots a module needs to travel one unit of distance, indexed by `ShipModuleType`
+    pub module_travel_slots: [u64; SHIP_MODULE_TYPES],
+    /// Percent of base stats a module gains with every level above the first
+    pub module_level_bonus_percent: u64,
+    /// Percent of the fleet's value refunded when it is disbanded
+    pub disband_refund_percent: u8,
+    /// Damage of a weapon against its matching surface, in percent of its base damage
+    pub weapon_surface_bonus_percent: u64,
+    /// Rounds before a battle ends undecided, capped by `MAX_ROUNDS`
+    pub max_rounds: u8,
+    /// Stats an `OrbitalBattery` adds per level
+    pub orbital_battery_stats: FleetStats,
+    /// Stats a `ShieldGenerator` adds per level
+    pub shield_generator_stats: FleetStats,
+    /// Slots after a battle until broken squadrons regroup
+    pub morale_recovery_slots: u64,
+    /// Explosive strength needed to knock a building down from level 1, scales with the level
+    pub bombardment_damage_per_level: u64,
+    /// Bombardment never lowers a building below this level
+    pub bombardment_level_floor: u8,
+    /// Repairing a level costs this percent of upgrading to it
+    pub repair_cost_percent: u64,
+    /// Resources a `HaulingBay` carries per level when plundering or salvaging
+    pub hauling_bay_capacity: u64,
+    /// Percent of the ships lost in a battle left behind as a `DebrisField`
+    pub debris_percent: u64,
+    /// Slots in one production tick
+    pub production_tick_slots: u64,
+    /// Unharvested production stops piling up after this many slots
+    pub max_production_slots: u64,
+    /// Resources produced per tick by every planet, even without an industry
+    pub base_production: u64,
+    /// Additional resources produced per tick for each level of the industry building
+    pub production_per_level: u64,
+    /// IGT produced per tick
+    pub igt_production: u64,
+    /// Resources put into the player cache with the first planet
+    pub first_planet_grant: Resources,
+    /// IGT minted to a newly registered player
+    pub registration_igt: u64,
+    /// Colonization cost for the first colony: [metal, crystal, chemical, fuel]
+    pub colonization_base_costs: [f32; 4],
+    /// IGT cost for the first colony
+    pub colonization_base_cost_igt: f32,
+    /// Each next colony costs this much more than the previous one
+    pub colonization_cost_factor: f32,
+    /// Slots a fleet has to wait between two salvages
+    pub salvage_cooldown_slots: u64,
+}
+
+impl BalanceValues {
+    pub fn building_base_cost(&self, building_type: BuildingType) -> [f32; 4] {
+        self.building_base_costs[building_type as usize]
+    }
+    pub fn hull_base_cost(&self, hull: HullClass) -> [f32; 4] {
+        self.hull_base_costs[hull as usize]
+    }
+    pub fn module_base_cost(&self, module_type: ShipModuleType) -> [f32; 4] {
+        self.module_base_costs[module_type as usize]
+    }
+    pub fn module_base_cost_igt(&self, module_type: ShipModuleType) -> f32 {
+        self.module_base_costs_igt[module_type as usize]
+    }
+    pub fn module_travel_slots(&self, module_type: ShipModuleType) -> u64 {
+        self.module_travel_slots[module_type as usize]
+    }
+    pub fn max_rounds(&self) -> usize {
+        (self.max_rounds as usize).min(MAX_ROUNDS)
+    }
+    /// Checks values that would break the formulas
+    pub fn validate(&self) -> Result<()> {
+        // Costs are converted into token amounts, NaN or infinity would turn them into garbage
+        let cost_is_valid = |cost: &f32| cost.is_finite() && *cost >= 0.0;
+        let costs_are_valid = self
+            .building_base_costs
+            .iter()
+            .chain(self.hull_base_costs.iter())
+            .chain(self.module_base_costs.iter())
+            .chain([self.colonization_base_costs].iter())
+            .flatten()
+            .chain(self.module_base_costs_igt.iter())
+            .chain([self.colonization_base_cost_igt].iter())
+            .all(cost_is_valid);
+        let factors_are_valid = [
+            self.building_upgrade_factor,
+            self.module_upgrade_factor,
+            self.colonization_cost_factor,
+        ]
+        .iter()
+        .all(|factor| factor.is_finite() && *factor >= 1.0);
+        if !costs_are_valid
+            || !factors_are_valid
+            || self.production_tick_slots == 0
+            || self.max_rounds == 0
+            || self.disband_refund_percent > 100
+            || self.debris_percent > 100
+            || self.weapon_surface_bonus_percent == 0
+            || self.bombardment_damage_per_level == 0
+            || self.bombardment_level_floor == 0
+            || self.module_level_bonus_percent > MAX_BONUS_PERCENT
+            || self.weapon_surface_bonus_percent > MAX_BONUS_PERCENT
+            || self.repair_cost_percent > MAX_BONUS_PERCENT
+            || self.hauling_bay_capacity > MAX_HAULING_BAY_CAPACITY
+            || self.production_tick_slots > MAX_SLOTS
+            || self.max_production_slots > MAX_SLOTS
+            || self.morale_recovery_slots > MAX_SLOTS
+            || self.salvage_cooldown_slots > MAX_SLOTS
+            || self.module_travel_slots.iter().any(|slots| *slots > MAX_SLOTS)
+            || self.base_production > MAX_TICK_PRODUCTION
+            || self.production_per_level > MAX_TICK_PRODUCTION
+            || self.igt_production > MAX_TICK_PRODUCTION
+        {
+            return Err(BalanceErrorCode::InvalidValues.into());
+        }
+        Ok(())
+    }
+}
+
+impl Default for BalanceValues {
+    fn default() -> Self {
+        Self {
+            building_upgrade_factor: 1.1,
+            building_base_costs: BuildingType::ALL.map(|b| b.default_base_upgrade_cost()),
+            hull_base_costs: HullClass::ALL.map(|h| h.default_base_cost()),
+            module_upgrade_factor: 1.6,
+            module_base_costs: ShipModuleType::ALL.map(|m| m.default_base_cost()),
+            module_base_costs_igt: ShipModuleType::ALL.map(|m| m.default_base_cost_igt()),
+            module_travel_slots: ShipModuleType::ALL.map(|m| m.default_travel_slots()),
+            module_level_bonus_percent: 20,
+            disband_refund_percent: 50,
+            weapon_surface_bonus_percent: 125,
+            max_rounds: MAX_ROUNDS as u8,
+            orbital_battery_stats: FleetStats {
+                weapons: Weapons::from_numbers(4, 4, 4),
+                defenses: Defenses::from_numbers(0, 4, 6),
+            },
+            shield_generator_stats: FleetStats {
+                weapons: Weapons::default(),
+                defenses: Defenses::from_numbers(12, 0, 4),
+            },
+            morale_recovery_slots: 150,
+            bombardment_damage_per_level: 10,
+            bombardment_level_floor: 1,
+            repair_cost_percent: 50,
+            hauling_bay_capacity: 25,
+            debris_percent: 30,
+            production_tick_slots: PRODUCTION_TICK_SLOTS,
+            max_production_slots: MAX_PRODUCTION_SLOTS,
+            base_production: BASE_PRODUCTION,
+            production_per_level: PRODUCTION_PER_LEVEL,
+            igt_production: IGT_PRODUCTION,
+            first_planet_grant: Resources {
+                igt: 10,
+                metal: 10,
+                crystal: 10,
+                chemical: 10,
+                fuel: 10,
+            },
+            registration_igt: 10,
+            colonization_base_costs: COLONIZATION_BASE_COST,
+            colonization_base_cost_igt: COLONIZATION_BASE_COST_IGT,
+            colonization_cost_factor: COLONIZATION_COST_FACTOR,
+            salvage_cooldown_slots: 150,
+        }
+    }
+}
+
+#[error_code]
+pub enum BalanceErrorCode {
+    #[msg("Balance values are out of range")]
+    InvalidValues,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_values_are_valid() {
+        assert!(BalanceValues::default().validate().is_ok());
+    }
+
+    #[test]
+    fn nan_costs_are_rejected() {
+        let mut values = BalanceValues::default();
+        values.module_base_costs[3][1] = f32::NAN;
+        assert!(values.validate().is_err());
+        let values = BalanceValues {
+            colonization_cost_factor: f32::NAN,
+            ..Default::default()
+        };
+        assert!(values.validate().is_err());
+        let values = BalanceValues {
+            colonization_base_cost_igt: f32::INFINITY,
+            ..Default::default()
+        };
+        assert!(values.validate().is_err());
+    }
+
+    #[test]
+    fn unbounded_slots_are_rejected() {
+        let mut values = BalanceValues::default();
+        values.module_travel_slots[0] = u64::MAX;
+        assert!(values.validate().is_err());
+    }
+}
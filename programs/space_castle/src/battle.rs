@@ -1,19 +1,23 @@
 use anchor_lang::prelude::*;
 
 use crate::{
-    fleet::{Fleet, ShipModule, ShipModuleType, MODULES_ON_SHIP, SQUADRONS_IN_FLEET},
+    balance::BalanceValues,
+    building::{Building, BuildingType},
+    fleet::{Fleet, ShipTemplate, SQUADRONS_IN_FLEET},
     resource::Resources,
 };
 
 pub const MAX_ROUNDS: usize = 16;
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Copy, Clone)]
+#[cfg_attr(feature = "simulator", derive(serde::Serialize, serde::Deserialize))]
 pub enum BattleSide {
     Attacker,
     Defender,
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Copy, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "simulator", derive(serde::Serialize, serde::Deserialize))]
 pub enum BattlePresence {
     #[default]
     Active,
@@ -22,6 +26,7 @@ pub enum BattlePresence {
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Copy, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "simulator", derive(serde::Serialize, serde::Deserialize))]
 pub enum Morale {
     #[default]
     Normal,
@@ -30,43 +35,140 @@ pub enum Morale {
 
 pub struct BattleResult {
     pub winner: BattleSide,
-    pub rounds: [Option<(FleetBattleRound, FleetBattleRound)>; MAX_ROUNDS],
+    pub rounds: [Option<BattleRound>; MAX_ROUNDS],
     pub att_losses: Resources,
     pub def_losses: Resources,
 }
 
+impl BattleResult {
+    /// Result of landing at an undefended position, the attacker wins without a fight
+    pub fn uncontested() -> Self {
+        Self {
+            winner: BattleSide::Attacker,
+            rounds: [None; MAX_ROUNDS],
+            att_losses: Resources::default(),
+            def_losses: Resources::default(),
+        }
+    }
+}
+
+#[account]
+#[derive(InitSpace)]
+/// Outcome of a battle, kept so it can be replayed and checked after the fact
+pub struct BattleReport {
+    /// Index among the attacker's reports
+    pub id: u64,
+    pub slot: u64,
+    pub attacker: Pubkey,
+    pub defender: Pubkey,
+    pub x: u16,
+    pub y: u16,
+    pub target_x: u16,
+    pub target_y: u16,
+    pub winner: BattleSide,
+    pub rounds: [Option<BattleRound>; MAX_ROUNDS],
+    pub att_losses: Resources,
+    pub def_losses: Resources,
+}
+
+#[account]
+#[derive(InitSpace)]
+/// Wreckage left at a position by battles, fleets with hauling bays can salvage it
+pub struct DebrisField {
+    /// Whole units, like `PlayerCache`
+    pub resources: Resources,
+}
+
+impl DebrisField {
+    /// Leaves a part of both sides' losses behind, IGT doesn't make it into the debris
+    ///
+    /// # Returns
+    ///
+    /// * Debris added by this battle
+    ///
+    pub fn add_losses(&mut self, result: &BattleResult, balance: &BalanceValues) -> Resources {
+        let debris = Resources {
+            igt: 0,
+            ..result
+                .att_losses
+                .sum(result.def_losses.clone())
+                .percent(balance.debris_percent)
+                .to_whole_units()
+        };
+        self.resources = self.resources.sum(debris.clone());
+        debris
+    }
+
+    /// Takes as much debris as fits into the cargo
+    pub fn salvage(&mut self, cargo_capacity: u64) -> Resources {
+        let salvaged = self.resources.load_cargo(cargo_capacity);
+        self.resources = self.resources.sub(salvaged.clone());
+        salvaged
+    }
+}
+
+impl BattleReport {
+    /// Stores the result of a battle between `attacker` at (x, y) and `defender` at
+    /// (target_x, target_y)
+    pub fn record(
+        &mut self,
+        id: u64,
+        (attacker, defender): (Pubkey, Pubkey),
+        ((x, y), (target_x, target_y)): ((u16, u16), (u16, u16)),
+        result: &BattleResult,
+    ) -> Result<()> {
+        self.id = id;
+        self.slot = Clock::get()?.slot;
+        self.attacker = attacker;
+        self.defender = defender;
+        self.x = x;
+        self.y = y;
+        self.target_x = target_x;
+        self.target_y = target_y;
+        self.winner = result.winner;
+        self.rounds = result.rounds;
+        self.att_losses = result.att_losses.clone();
+        self.def_losses = result.def_losses.clone();
+        Ok(())
+    }
+}
+
 #[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Default, Copy, Clone)]
+#[cfg_attr(feature = "simulator", derive(serde::Serialize, serde::Deserialize))]
 pub struct FleetStats {
     pub weapons: Weapons,
     pub defenses: Defenses,
 }
 
 impl FleetStats {
-    pub fn from_modules(modules: &[ShipModule; MODULES_ON_SHIP]) -> Self {
+    /// Stats of one ship, the hull gives base defenses and every level of a
+    /// module above the first adds `module_level_bonus_percent` of its base stats
+    ///
+    /// The level of the building that unlocks a module caps it when the ship is built or
+    /// refitted, see `ShipModuleType::can_be_built`. Ships keep the level they were built
+    /// with after that, also away from the planet or after the building was bombarded.
+    pub fn from_template(template: &ShipTemplate, balance: &BalanceValues) -> Self {
         let mut weapons = Weapons {
             kinetic: 0,
             laser: 0,
             explosive: 0,
         };
-        let mut defenses = Defenses {
-            armor: 0,
-            shield: 0,
-            hull: 0,
-        };
-        for module in modules
-            .iter()
-            .filter(|m| !m.module_type.eq(&ShipModuleType::None))
-        {
+        let mut defenses = template.hull.base_defenses();
+        for module in template.mounted() {
+            let percent = balance
+                .module_level_bonus_percent
+                .saturating_mul(module.level.saturating_sub(1) as u64)
+                .saturating_add(100);
             let Weapons {
                 kinetic,
                 laser,
                 explosive,
-            } = module.module_type.base_weapons();
+            } = module.module_type.base_weapons().scale(percent);
             let Defenses {
                 armor,
                 shield,
                 hull,
-            } = module.module_type.base_defenses();
+            } = module.module_type.base_defenses().scale(percent);
             weapons.kinetic += kinetic;
             weapons.laser += laser;
             weapons.explosive += explosive;
@@ -79,6 +181,7 @@ impl FleetStats {
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Default, Copy, Clone)]
+#[cfg_attr(feature = "simulator", derive(serde::Serialize, serde::Deserialize))]
 pub struct Weapons {
     pub kinetic: u64,
     pub laser: u64,
@@ -86,13 +189,22 @@ pub struct Weapons {
 }
 
 impl Weapons {
+    /// Splits weapons into `parts` equal parts, nothing is left to split into zero parts
     pub fn divide(&self, parts: u8) -> Weapons {
+        if parts == 0 {
+            return Weapons::default();
+        }
         Weapons {
-            kinetic: self.kinetic.saturating_div(parts as u64),
-            laser: self.laser.saturating_div(parts as u64),
-            explosive: self.explosive.saturating_div(parts as u64),
+            kinetic: self.kinetic / parts as u64,
+            laser: self.laser / parts as u64,
+            explosive: self.explosive / parts as u64,
         }
     }
+    pub fn add(&mut self, other: &Weapons) {
+        self.kinetic = self.kinetic.saturating_add(other.kinetic);
+        self.laser = self.laser.saturating_add(other.laser);
+        self.explosive = self.explosive.saturating_add(other.explosive);
+    }
     pub fn multiply(&self, times: u16) -> Weapons {
         Weapons {
             kinetic: self.kinetic.saturating_mul(times as u64),
@@ -100,6 +212,14 @@ impl Weapons {
             explosive: self.explosive.saturating_mul(times as u64),
         }
     }
+    /// Scales all weapons to `percent` of their value
+    pub fn scale(&self, percent: u64) -> Weapons {
+        Weapons {
+            kinetic: self.kinetic.saturating_mul(percent) / 100,
+            laser: self.laser.saturating_mul(percent) / 100,
+            explosive: self.explosive.saturating_mul(percent) / 100,
+        }
+    }
     pub fn from_numbers(laser: u64, kinetic: u64, explosive: u64) -> Self {
         Weapons {
             kinetic,
@@ -110,6 +230,7 @@ impl Weapons {
 }
 
 #[derive(Default, InitSpace, Copy, Clone, AnchorSerialize, AnchorDeserialize)]
+#[cfg_attr(feature = "simulator", derive(serde::Serialize, serde::Deserialize))]
 pub struct Defenses {
     pub armor: u64,
     pub shield: u64,
@@ -120,10 +241,18 @@ impl Defenses {
     pub fn multiply(&self, times: u16) -> Defenses {
         Defenses {
             armor: self.armor.saturating_mul(times as u64),
-            shield: self.armor.saturating_mul(times as u64),
+            shield: self.shield.saturating_mul(times as u64),
             hull: self.hull.saturating_mul(times as u64),
         }
     }
+    /// Scales all defenses to `percent` of their value
+    pub fn scale(&self, percent: u64) -> Defenses {
+        Defenses {
+            armor: self.armor.saturating_mul(percent) / 100,
+            shield: self.shield.saturating_mul(percent) / 100,
+            hull: self.hull.saturating_mul(percent) / 100,
+        }
+    }
     pub fn from_numbers(shield: u64, armor: u64, hull: u64) -> Self {
         Defenses {
             shield,
@@ -131,37 +260,189 @@ impl Defenses {
             hull,
         }
     }
+    /// Sum of all surfaces
+    pub fn total(&self) -> u128 {
+        self.shield as u128 + self.armor as u128 + self.hull as u128
+    }
+    /// Damage of each weapon goes through shield, armor and hull. Whatever a surface does
+    /// not absorb moves on to the next one without the bonus of the previous surface.
+    pub fn take_hits(&mut self, weapons: &Weapons, bonus_percent: u64) {
+        let mut surfaces = [self.shield, self.armor, self.hull];
+        for (weapon, base_damage) in [weapons.laser, weapons.kinetic, weapons.explosive]
+            .iter()
+            .enumerate()
+        {
+            let mut damage = *base_damage;
+            for (surface, health) in surfaces.iter_mut().enumerate() {
+                if damage == 0 {
+                    break;
+                }
+                if *health == 0 {
+                    continue;
+                }
+                let modified = with_modifier(damage, weapon, surface, bonus_percent);
+                if modified <= *health {
+                    *health -= modified;
+                    damage = 0;
+                } else {
+                    damage = without_modifier(modified - *health, weapon, surface, bonus_percent);
+                    *health = 0;
+                }
+            }
+        }
+        [self.shield, self.armor, self.hull] = surfaces;
+    }
+}
+
+/// Adds modifier of a weapons on a surface to damage
+///
+/// weapons: 0 - laser, 1 - kinetic, 2 - explosive
+/// surface: 0 - shield, 1 - armor, 2 - hull
+fn with_modifier(damage: u64, weapon: usize, surface: usize, bonus_percent: u64) -> u64 {
+    if weapon == surface {
+        (damage as u128 * bonus_percent as u128 / 100).min(u64::MAX as u128) as u64
+    } else {
+        damage
+    }
+}
+
+/// Remove modifier of a weapons on a surface damage, a zero bonus leaves damage as is
+///
+/// weapons: 0 - laser, 1 - kinetic, 2 - explosive
+/// surface: 0 - shield, 1 - armor, 2 - hull
+fn without_modifier(damage: u64, weapon: usize, surface: usize, bonus_percent: u64) -> u64 {
+    if weapon == surface && bonus_percent > 0 {
+        (damage as u128 * 100 / bonus_percent as u128).min(u64::MAX as u128) as u64
+    } else {
+        damage
+    }
 }
 
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Copy, Clone)]
+#[cfg_attr(feature = "simulator", derive(serde::Serialize, serde::Deserialize))]
 pub struct FleetBattleRound {
     pub losses: [u16; SQUADRONS_IN_FLEET],
     pub morale: [Morale; SQUADRONS_IN_FLEET],
     pub presence: [BattlePresence; SQUADRONS_IN_FLEET],
 }
 
-pub fn fleet_battle(attacker_fleet: &mut Fleet, defender_fleet: &mut Fleet) -> BattleResult {
-    let att_init_cost = attacker_fleet.get_quote();
-    let def_init_cost = defender_fleet.get_quote();
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Copy, Clone)]
+#[cfg_attr(feature = "simulator", derive(serde::Serialize, serde::Deserialize))]
+pub struct BattleRound {
+    pub attacker: FleetBattleRound,
+    pub defender: FleetBattleRound,
+    /// Defenses of the planet left after the round
+    pub planet: Defenses,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Default, Copy, Clone)]
+#[cfg_attr(feature = "simulator", derive(serde::Serialize, serde::Deserialize))]
+/// Defense buildings of a planet, they fight on the defender's side when the planet's
+/// position is attacked
+pub struct PlanetDefenses {
+    pub weapons: Weapons,
+    pub defenses: Defenses,
+}
+
+impl PlanetDefenses {
+    /// Every level of a defense building adds its stats from the balance config
+    pub fn from_levels(orbital_battery: u8, shield_generator: u8, balance: &BalanceValues) -> Self {
+        let battery = balance.orbital_battery_stats;
+        let shield = balance.shield_generator_stats;
+        let mut planet = PlanetDefenses::default();
+        for (stats, level) in [(battery, orbital_battery), (shield, shield_generator)] {
+            let Weapons {
+                kinetic,
+                laser,
+                explosive,
+            } = stats.weapons.multiply(level as u16);
+            let Defenses {
+                armor,
+                shield,
+                hull,
+            } = stats.defenses.multiply(level as u16);
+            planet.weapons.kinetic += kinetic;
+            planet.weapons.laser += laser;
+            planet.weapons.explosive += explosive;
+            planet.defenses.shield += shield;
+            planet.defenses.armor += armor;
+            planet.defenses.hull += hull;
+        }
+        planet
+    }
+
+    pub fn from_buildings(buildings: &[Building], balance: &BalanceValues) -> Self {
+        let level = |building_type: BuildingType| {
+            buildings
+                .iter()
+                .find(|b| b.building_type.eq(&building_type))
+                .map_or(0, |b| b.level)
+        };
+        Self::from_levels(
+            level(BuildingType::OrbitalBattery),
+            level(BuildingType::ShieldGenerator),
+            balance,
+        )
+    }
+
+    /// Planet keeps fighting until its hull is gone
+    pub fn is_standing(&self) -> bool {
+        self.defenses.hull > 0
+    }
+
+    pub fn take_damage(&mut self, weapons: &Weapons, balance: &BalanceValues) {
+        self.defenses
+            .take_hits(weapons, balance.weapon_surface_bonus_percent);
+        if !self.is_standing() {
+            self.weapons = Weapons::default();
+        }
+    }
+}
+
+/// Fights a battle between two fleets, defense buildings of the planet at the defender's
+/// position join on the defender's side. The defender holds if the attacker retreats or the
+/// planet is left standing, otherwise a battle that runs out of rounds goes to the attacker.
+pub fn fleet_battle(
+    attacker_fleet: &mut Fleet,
+    defender_fleet: &mut Fleet,
+    planet: &mut PlanetDefenses,
+    balance: &BalanceValues,
+) -> BattleResult {
+    let att_init_cost = attacker_fleet.get_quote(balance);
+    let def_init_cost = defender_fleet.get_quote(balance);
+    let max_rounds = balance.max_rounds();
     let mut round = 0;
-    let mut rounds: [Option<(FleetBattleRound, FleetBattleRound)>; MAX_ROUNDS] = [
-        None, None, None, None, None, None, None, None, None, None, None, None, None, None, None,
-        None,
-    ];
-    while !attacker_fleet.in_retreat() && !defender_fleet.in_retreat() && round < MAX_ROUNDS {
-        let att_weapons = &attacker_fleet.get_battle_strength().weapons;
-        let def_weapons = &defender_fleet.get_battle_strength().weapons;
-        let att_round = attacker_fleet.take_losses(def_weapons);
-        let def_round = defender_fleet.take_losses(att_weapons);
-        rounds[round] = Some((att_round, def_round));
+    let mut rounds: [Option<BattleRound>; MAX_ROUNDS] = [None; MAX_ROUNDS];
+    while !attacker_fleet.in_retreat()
+        && (!defender_fleet.in_retreat() || planet.is_standing())
+        && round < max_rounds
+    {
+        let att_weapons = attacker_fleet.get_battle_strength(balance).weapons;
+        let mut def_weapons = defender_fleet.get_battle_strength(balance).weapons;
+        def_weapons.add(&planet.weapons);
+        // Attacker splits its fire between the defending fleet and the planet
+        let (fleet_fire, planet_fire) = match (defender_fleet.in_retreat(), planet.is_standing()) {
+            (false, true) => (att_weapons.divide(2), att_weapons.divide(2)),
+            (true, true) => (Weapons::default(), att_weapons),
+            _ => (att_weapons, Weapons::default()),
+        };
+        let att_round = attacker_fleet.take_losses(&def_weapons, balance);
+        let def_round = defender_fleet.take_losses(&fleet_fire, balance);
+        planet.take_damage(&planet_fire, balance);
+        rounds[round] = Some(BattleRound {
+            attacker: att_round,
+            defender: def_round,
+            planet: planet.defenses,
+        });
         round += 1;
     }
-    let winner = if attacker_fleet.in_retreat() {
+    let winner = if attacker_fleet.in_retreat() || planet.is_standing() {
         BattleSide::Defender
     } else {
         BattleSide::Attacker
     };
-    let att_new_cost = attacker_fleet.get_quote();
-    let def_new_cost = defender_fleet.get_quote();
+    let att_new_cost = attacker_fleet.get_quote(balance);
+    let def_new_cost = defender_fleet.get_quote(balance);
     BattleResult {
         winner,
         rounds,
@@ -169,3 +450,113 @@ pub fn fleet_battle(attacker_fleet: &mut Fleet, defender_fleet: &mut Fleet) -> B
         def_losses: def_init_cost.sub(def_new_cost),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fleet::{HullClass, ShipModule, ShipModuleType, SquadronBlueprint, MODULES_ON_SHIP};
+
+    fn gunship(level: u8) -> ShipTemplate {
+        let mut modules = [ShipModule::default(); MODULES_ON_SHIP];
+        modules[0] = ShipModule {
+            module_type: ShipModuleType::MachineGun,
+            level,
+        };
+        ShipTemplate {
+            hull: HullClass::Corvette,
+            modules,
+        }
+    }
+
+    fn carrier(amount: u16) -> Fleet {
+        let mut modules = [ShipModule::default(); MODULES_ON_SHIP];
+        modules[0] = ShipModule {
+            module_type: ShipModuleType::HaulingBay,
+            level: 1,
+        };
+        let template = ShipTemplate {
+            hull: HullClass::Carrier,
+            modules,
+        };
+        let mut blueprints = [None; SQUADRONS_IN_FLEET];
+        blueprints[0] = Some(SquadronBlueprint::new(template, amount));
+        Fleet::from_blueprints(Pubkey::new_unique(), blueprints).unwrap()
+    }
+
+    #[test]
+    fn dividing_into_zero_parts_leaves_nothing() {
+        let weapons = Weapons::from_numbers(3, 6, 9);
+        let none = weapons.divide(0);
+        assert_eq!((none.laser, none.kinetic, none.explosive), (0, 0, 0));
+        let half = weapons.divide(2);
+        assert_eq!((half.laser, half.kinetic, half.explosive), (1, 3, 4));
+    }
+
+    #[test]
+    fn multiply_scales_every_surface_by_itself() {
+        let defenses = Defenses::from_numbers(1, 2, 3).multiply(4);
+        assert_eq!((defenses.shield, defenses.armor, defenses.hull), (4, 8, 12));
+    }
+
+    #[test]
+    fn hits_on_nothing_change_nothing() {
+        let mut defenses = Defenses::default();
+        defenses.take_hits(&Weapons::from_numbers(5, 5, 5), 125);
+        assert_eq!(defenses.total(), 0);
+    }
+
+    #[test]
+    fn leftover_damage_moves_on_without_the_bonus() {
+        let mut defenses = Defenses::from_numbers(10, 10, 10);
+        // 15 lasers hit the shield for 18, the leftover 8 is 6 without the bonus
+        defenses.take_hits(&Weapons::from_numbers(15, 0, 0), 125);
+        assert_eq!((defenses.shield, defenses.armor, defenses.hull), (0, 4, 10));
+    }
+
+    #[test]
+    fn module_keeps_the_level_it_was_built_with() {
+        let balance = BalanceValues::default();
+        let mut buildings = [Building::default(); 6];
+        buildings[0] = Building {
+            level: 3,
+            building_type: BuildingType::MetalIndustry,
+            damaged_levels: 0,
+        };
+        assert!(ShipModuleType::MachineGun.can_be_built(3, buildings).is_ok());
+        // Bombarded down to level 1, the building caps new guns but not the built ones
+        buildings[0].level = 1;
+        assert!(ShipModuleType::MachineGun.can_be_built(3, buildings).is_err());
+        let stats = FleetStats::from_template(&gunship(3), &balance);
+        let scaled = ShipModuleType::MachineGun
+            .base_weapons()
+            .scale(100 + 2 * balance.module_level_bonus_percent);
+        assert_eq!(stats.weapons.kinetic, scaled.kinetic);
+    }
+
+    #[test]
+    fn battle_that_runs_out_of_rounds_goes_to_the_attacker() {
+        let balance = BalanceValues::default();
+        let mut attacker = carrier(1);
+        let mut defender = carrier(5);
+        let mut planet = PlanetDefenses::from_buildings(&[], &balance);
+        let result = fleet_battle(&mut attacker, &mut defender, &mut planet, &balance);
+        assert!(matches!(result.winner, BattleSide::Attacker));
+        assert!(result
+            .rounds
+            .iter()
+            .take(balance.max_rounds())
+            .all(|r| r.is_some()));
+        // Defending fleet is still there, `planet_invade` can't land while it is
+        assert!(!defender.in_retreat());
+    }
+
+    #[test]
+    fn standing_planet_holds_a_battle_that_runs_out_of_rounds() {
+        let balance = BalanceValues::default();
+        let mut attacker = carrier(1);
+        let mut defender = carrier(5);
+        let mut planet = PlanetDefenses::from_levels(0, 1, &balance);
+        let result = fleet_battle(&mut attacker, &mut defender, &mut planet, &balance);
+        assert!(matches!(result.winner, BattleSide::Defender));
+    }
+}
@@ -0,0 +1,175 @@
+//! Runs `fleet_battle` off-chain with the same code the program uses.
+//!
+//! Reads one battle or an array of battles as JSON from the file given as the first argument
+//! or from stdin and prints the outcome of every battle as JSON. Build it with
+//! `cargo run --features simulator --bin battle_sim -- battles.json`.
+//!
+//! ```json
+//! {
+//!   "attacker": [
+//!     { "hull": "Frigate", "modules": [{ "module_type": "MachineGun", "level": 1 }], "amount": 3 }
+//!   ],
+//!   "defender": [
+//!     { "hull": "Cruiser", "modules": [{ "module_type": "Lasers", "level": 2 }], "amount": 1 }
+//!   ]
+//! }
+//! ```
+//!
+//! `planet` adds defense buildings on the defender's side, e.g.
+//! `"planet": { "orbital_battery": 2, "shield_generator": 1 }`, and `balance` can be set on a
+//! battle to simulate with other `BalanceValues` than the defaults.
+//! Losses are reported in base units of each resource.
+
+use std::{
+    fs,
+    io::{self, Read},
+    process::ExitCode,
+};
+
+use anchor_lang::prelude::Pubkey;
+use serde::{Deserialize, Serialize};
+use space_castle::{
+    balance::BalanceValues,
+    battle::{fleet_battle, BattleRound, BattleSide, PlanetDefenses},
+    fleet::{
+        validate_ship_template, Fleet, HullClass, ShipModule, ShipTemplate, SquadronBlueprint,
+        MODULES_ON_SHIP, SQUADRONS_IN_FLEET,
+    },
+    resource::Resources,
+};
+
+#[derive(Deserialize)]
+struct SquadronInput {
+    hull: HullClass,
+    modules: Vec<ShipModule>,
+    amount: u16,
+}
+
+#[derive(Deserialize, Default)]
+struct PlanetInput {
+    #[serde(default)]
+    orbital_battery: u8,
+    #[serde(default)]
+    shield_generator: u8,
+}
+
+#[derive(Deserialize)]
+struct BattleInput {
+    attacker: Vec<SquadronInput>,
+    defender: Vec<SquadronInput>,
+    #[serde(default)]
+    planet: PlanetInput,
+    #[serde(default)]
+    balance: Option<BalanceValues>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Input {
+    One(Box<BattleInput>),
+    Batch(Vec<BattleInput>),
+}
+
+#[derive(Serialize)]
+struct BattleOutput {
+    winner: BattleSide,
+    rounds: Vec<BattleRound>,
+    attacker_losses: Resources,
+    defender_losses: Resources,
+    attacker_survivors: [u16; SQUADRONS_IN_FLEET],
+    defender_survivors: [u16; SQUADRONS_IN_FLEET],
+}
+
+fn build_fleet(squadrons: Vec<SquadronInput>) -> Result<Fleet, String> {
+    if squadrons.len() > SQUADRONS_IN_FLEET {
+        return Err(format!(
+            "fleet has {} squadrons, at most {SQUADRONS_IN_FLEET} are allowed",
+            squadrons.len()
+        ));
+    }
+    let mut blueprints = [None; SQUADRONS_IN_FLEET];
+    for (index, squadron) in squadrons.into_iter().enumerate() {
+        if squadron.modules.len() > MODULES_ON_SHIP {
+            return Err(format!(
+                "squadron {index} has {} modules, at most {MODULES_ON_SHIP} are allowed",
+                squadron.modules.len()
+            ));
+        }
+        let mut template = ShipTemplate {
+            hull: squadron.hull,
+            modules: [ShipModule::default(); MODULES_ON_SHIP],
+        };
+        template.modules[..squadron.modules.len()].copy_from_slice(&squadron.modules);
+        validate_ship_template(&template).map_err(|e| format!("squadron {index}: {e}"))?;
+        blueprints[index] = Some(SquadronBlueprint::new(template, squadron.amount));
+    }
+    Fleet::from_blueprints(Pubkey::default(), blueprints).map_err(|e| e.to_string())
+}
+
+fn survivors(fleet: &Fleet) -> [u16; SQUADRONS_IN_FLEET] {
+    fleet
+        .squadrons()
+        .map(|s| s.map(|s| s.amount()).unwrap_or(0))
+}
+
+fn simulate(input: BattleInput) -> Result<BattleOutput, String> {
+    let balance = input.balance.unwrap_or_default();
+    balance.validate().map_err(|e| e.to_string())?;
+    let mut attacker = build_fleet(input.attacker).map_err(|e| format!("attacker {e}"))?;
+    let mut defender = build_fleet(input.defender).map_err(|e| format!("defender {e}"))?;
+    let mut planet = PlanetDefenses::from_levels(
+        input.planet.orbital_battery,
+        input.planet.shield_generator,
+        &balance,
+    );
+    let result = fleet_battle(&mut attacker, &mut defender, &mut planet, &balance);
+    Ok(BattleOutput {
+        winner: result.winner,
+        rounds: result.rounds.into_iter().flatten().collect(),
+        attacker_losses: result.att_losses,
+        defender_losses: result.def_losses,
+        attacker_survivors: survivors(&attacker),
+        defender_survivors: survivors(&defender),
+    })
+}
+
+fn read_input() -> Result<String, String> {
+    match std::env::args().nth(1) {
+        Some(path) => fs::read_to_string(&path).map_err(|e| format!("{path}: {e}")),
+        None => {
+            let mut buffer = String::new();
+            io::stdin()
+                .read_to_string(&mut buffer)
+                .map_err(|e| e.to_string())?;
+            Ok(buffer)
+        }
+    }
+}
+
+fn run() -> Result<String, String> {
+    let input: Input = serde_json::from_str(&read_input()?).map_err(|e| e.to_string())?;
+    let output = match input {
+        Input::One(battle) => serde_json::to_string(&simulate(*battle)?),
+        Input::Batch(battles) => serde_json::to_string(
+            &battles
+                .into_iter()
+                .enumerate()
+                .map(|(index, battle)| simulate(battle).map_err(|e| format!("battle {index}: {e}")))
+                .collect::<Result<Vec<_>, _>>()?,
+        ),
+    };
+    output.map_err(|e| e.to_string())
+}
+
+fn main() -> ExitCode {
+    match run() {
+        Ok(output) => {
+            println!("{output}");
+            ExitCode::SUCCESS
+        }
+        Err(error) => {
+            eprintln!("error: {error}");
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -1,6 +1,7 @@
 use anchor_lang::prelude::*;
 
 use crate::{
+    balance::{BalanceValues, BUILDING_TYPES},
     mint_decimals,
     resource::Resources,
     utilities::{calculate_upgrade_cost, convert_from_float},
@@ -11,15 +12,16 @@ use crate::{
 pub struct Building {
     pub level: u8,
     pub building_type: BuildingType,
+    /// Levels lost to bombardment that can still be repaired
+    pub damaged_levels: u8,
 }
 
 impl Building {
-    const UPGRADE_FACTOR: f32 = 1.1;
-
     pub fn default() -> Self {
         Building {
             level: 0,
             building_type: BuildingType::None,
+            damaged_levels: 0,
         }
     }
 
@@ -27,6 +29,7 @@ impl Building {
         Building {
             level: 1,
             building_type: BuildingType::PlanetaryCapital,
+            damaged_levels: 0,
         }
     }
 
@@ -34,6 +37,7 @@ impl Building {
         Building {
             level: 1,
             building_type: BuildingType::Shipyard,
+            damaged_levels: 0,
         }
     }
 
@@ -49,6 +53,7 @@ impl Building {
         Building {
             level: 1,
             building_type,
+            damaged_levels: 0,
         }
     }
 
@@ -58,22 +63,8 @@ impl Building {
     ///
     /// * [`metal`, `crystal`, `chemical`, `fuel`]
     ///
-    pub fn base_upgrade_cost(&self) -> [f32; 4] {
-        match self.building_type {
-            // General buildings
-            BuildingType::Shipyard => [100.0, 100.0, 100.0, 100.0],
-            BuildingType::AstralNavyHQ => [50.0, 50.0, 50.0, 150.0],
-            BuildingType::TradeBeacon => [250.0, 250.0, 250.0, 250.0],
-            BuildingType::Infrastructure => [20.0, 20.0, 20.0, 50.0],
-            BuildingType::PlanetaryCapital => [20.0, 20.0, 20.0, 100.0],
-            // Resource buildings
-            BuildingType::MetalIndustry => [10.0, 5.0, 5.0, 10.0],
-            BuildingType::CrystalLabs => [5.0, 10.0, 5.0, 10.0],
-            BuildingType::ChemicalRefinery => [5.0, 5.0, 10.0, 10.0],
-            BuildingType::FuelExtractors => [50.0, 50.0, 50.0, 150.0],
-            // Should never happen
-            BuildingType::None => [0.0, 0.0, 0.0, 0.0],
-        }
+    pub fn base_upgrade_cost(&self, balance: &BalanceValues) -> [f32; 4] {
+        balance.building_base_cost(self.building_type)
     }
 
     /// Calculates the upgrade cost for this building
@@ -83,28 +74,75 @@ impl Building {
     /// A tuple where its params are quantities of resources each u16:
     /// * [`metal`, `crystal`, `chemical`, `fuel`]
     ///
-    pub fn calculate_upgrade_cost(&self) -> Resources {
-        let [metal, crystal, chemical, fuel] = self.base_upgrade_cost();
+    pub fn calculate_upgrade_cost(&self, balance: &BalanceValues) -> Resources {
+        let [metal, crystal, chemical, fuel] = self.base_upgrade_cost(balance);
+        let factor = balance.building_upgrade_factor;
         Resources {
             igt: 0,
             metal: convert_from_float(
-                calculate_upgrade_cost(metal, Building::UPGRADE_FACTOR, self.level),
+                calculate_upgrade_cost(metal, factor, self.level),
                 mint_decimals::METAL,
             ),
             crystal: convert_from_float(
-                calculate_upgrade_cost(crystal, Building::UPGRADE_FACTOR, self.level),
+                calculate_upgrade_cost(crystal, factor, self.level),
                 mint_decimals::CRYSTAL,
             ),
             chemical: convert_from_float(
-                calculate_upgrade_cost(chemical, Building::UPGRADE_FACTOR, self.level),
+                calculate_upgrade_cost(chemical, factor, self.level),
                 mint_decimals::CHEMICAL,
             ),
             fuel: convert_from_float(
-                calculate_upgrade_cost(fuel, Building::UPGRADE_FACTOR, self.level),
+                calculate_upgrade_cost(fuel, factor, self.level),
                 mint_decimals::FUEL,
             ),
         }
     }
+
+    /// Explosive strength knocks levels off the building, every level costs
+    /// `bombardment_damage_per_level` times the level it is knocked from. The building never
+    /// drops below `bombardment_level_floor`.
+    ///
+    /// # Returns
+    ///
+    /// * Number of levels lost
+    ///
+    pub fn bombard(&mut self, explosive: u64, balance: &BalanceValues) -> u8 {
+        let mut strength = explosive;
+        let mut levels_lost = 0;
+        while self.level > balance.bombardment_level_floor {
+            let needed = balance
+                .bombardment_damage_per_level
+                .saturating_mul(self.level as u64);
+            if strength < needed {
+                break;
+            }
+            strength -= needed;
+            self.level -= 1;
+            self.damaged_levels = self.damaged_levels.saturating_add(1);
+            levels_lost += 1;
+        }
+        levels_lost
+    }
+
+    /// Raises the building by a level. A level lost to bombardment is rebuilt first, so it
+    /// can't be repaired on top of the upgrade anymore.
+    pub fn upgrade(&mut self) {
+        self.level = self.level.saturating_add(1);
+        self.damaged_levels = self.damaged_levels.saturating_sub(1);
+    }
+
+    /// Cost of restoring all levels lost to bombardment, a part of what upgrading to them costs
+    pub fn calculate_repair_cost(&self, balance: &BalanceValues) -> Resources {
+        let mut cost = Resources::default();
+        for level in 1..=self.damaged_levels {
+            let restored = Building {
+                level: self.level.saturating_add(level),
+                ..*self
+            };
+            cost = cost.sum(restored.calculate_upgrade_cost(balance));
+        }
+        cost.percent(balance.repair_cost_percent)
+    }
 }
 
 #[derive(
@@ -121,9 +159,55 @@ pub enum BuildingType {
     TradeBeacon,
     AstralNavyHQ,
     Infrastructure,
+    /// Planetary defense that fires at attackers
+    OrbitalBattery,
+    /// Planetary defense that absorbs damage
+    ShieldGenerator,
 }
 
 impl BuildingType {
+    pub const ALL: [BuildingType; BUILDING_TYPES] = [
+        BuildingType::None,
+        BuildingType::PlanetaryCapital,
+        BuildingType::Shipyard,
+        BuildingType::MetalIndustry,
+        BuildingType::CrystalLabs,
+        BuildingType::ChemicalRefinery,
+        BuildingType::FuelExtractors,
+        BuildingType::TradeBeacon,
+        BuildingType::AstralNavyHQ,
+        BuildingType::Infrastructure,
+        BuildingType::OrbitalBattery,
+        BuildingType::ShieldGenerator,
+    ];
+
+    /// Base upgrade cost the balance config starts with
+    ///
+    /// # Returns
+    ///
+    /// * [`metal`, `crystal`, `chemical`, `fuel`]
+    ///
+    pub fn default_base_upgrade_cost(&self) -> [f32; 4] {
+        match self {
+            // General buildings
+            BuildingType::Shipyard => [100.0, 100.0, 100.0, 100.0],
+            BuildingType::AstralNavyHQ => [50.0, 50.0, 50.0, 150.0],
+            BuildingType::TradeBeacon => [250.0, 250.0, 250.0, 250.0],
+            BuildingType::Infrastructure => [20.0, 20.0, 20.0, 50.0],
+            BuildingType::PlanetaryCapital => [20.0, 20.0, 20.0, 100.0],
+            // Resource buildings
+            BuildingType::MetalIndustry => [10.0, 5.0, 5.0, 10.0],
+            BuildingType::CrystalLabs => [5.0, 10.0, 5.0, 10.0],
+            BuildingType::ChemicalRefinery => [5.0, 5.0, 10.0, 10.0],
+            BuildingType::FuelExtractors => [50.0, 50.0, 50.0, 150.0],
+            // Defense buildings
+            BuildingType::OrbitalBattery => [120.0, 60.0, 120.0, 40.0],
+            BuildingType::ShieldGenerator => [60.0, 150.0, 90.0, 40.0],
+            // Should never happen
+            BuildingType::None => [0.0, 0.0, 0.0, 0.0],
+        }
+    }
+
     pub fn from_str(input: &str) -> Result<BuildingType> {
         match input {
             "Infrastructure" => Ok(BuildingType::Infrastructure),
@@ -135,6 +219,8 @@ impl BuildingType {
             "AstralNavyHQ" => Ok(BuildingType::AstralNavyHQ),
             "Shipyard" => Ok(BuildingType::Shipyard),
             "TradeBeacon" => Ok(BuildingType::TradeBeacon),
+            "OrbitalBattery" => Ok(BuildingType::OrbitalBattery),
+            "ShieldGenerator" => Ok(BuildingType::ShieldGenerator),
             _ => Err(BuildingErrorCode::BuildingKey.into()),
         }
     }
@@ -148,6 +234,12 @@ pub fn generate_initial_buildings_for_planet(resources: [u16; 4]) -> [Building;
     starting_buildings
 }
 
+pub fn generate_initial_buildings_for_colony(resources: [u16; 4]) -> [Building; 6] {
+    let mut starting_buildings = [Building::default(); 6];
+    starting_buildings[0] = Building::default_industry(resources);
+    starting_buildings
+}
+
 #[error_code]
 pub enum BuildingErrorCode {
     #[msg("Building key given doesn't match any buildings")]
@@ -158,4 +250,47 @@ pub enum BuildingErrorCode {
     NoBuildingSpotLeft,
     #[msg("BuildingNotPresent")]
     BuildingNotPresent,
+    #[msg("Building has no levels to repair")]
+    NothingToRepair,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn shipyard(level: u8) -> Building {
+        Building {
+            level,
+            ..Building::default_shipyard()
+        }
+    }
+
+    #[test]
+    fn bombardment_stops_at_the_floor() {
+        let balance = BalanceValues::default();
+        let mut building = shipyard(3);
+        // Knocking down level 3 and 2 takes 30 + 20, level 1 is the floor
+        assert_eq!(building.bombard(1000, &balance), 2);
+        assert_eq!((building.level, building.damaged_levels), (1, 2));
+    }
+
+    #[test]
+    fn weak_bombardment_does_nothing() {
+        let balance = BalanceValues::default();
+        let mut building = shipyard(3);
+        assert_eq!(building.bombard(29, &balance), 0);
+        assert_eq!((building.level, building.damaged_levels), (3, 0));
+    }
+
+    #[test]
+    fn upgrade_rebuilds_a_damaged_level_first() {
+        let balance = BalanceValues::default();
+        let mut building = shipyard(3);
+        building.bombard(50, &balance);
+        building.upgrade();
+        assert_eq!((building.level, building.damaged_levels), (2, 1));
+        building.upgrade();
+        assert_eq!((building.level, building.damaged_levels), (3, 0));
+        assert!(building.calculate_repair_cost(&balance).is_empty());
+    }
 }
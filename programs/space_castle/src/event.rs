@@ -0,0 +1,252 @@
+use anchor_lang::prelude::*;
+
+use crate::{battle::BattleSide, building::BuildingType, resource::Resources};
+
+/// Game
+#[event]
+pub struct GameAdminChanged {
+    pub admin: Pubkey,
+}
+
+#[event]
+pub struct BalanceConfigUpdated {
+    pub version: u32,
+}
+
+/// Player
+#[event]
+pub struct PlayerRegistered {
+    pub player: Pubkey,
+    pub name: String,
+}
+
+#[event]
+pub struct PlayerResourceCacheClaimed {
+    pub player: Pubkey,
+    pub resources: Resources,
+}
+
+/// Planet
+#[event]
+pub struct PlanetClaimed {
+    pub player: Pubkey,
+    pub x: u16,
+    pub y: u16,
+    /// First claim is free, colonies are paid for
+    pub cost: Resources,
+}
+
+#[event]
+pub struct PlanetHarvested {
+    pub player: Pubkey,
+    pub x: u16,
+    pub y: u16,
+    pub resources: Resources,
+}
+
+#[event]
+pub struct PlanetInvaded {
+    pub battle_id: u64,
+    pub attacker: Pubkey,
+    /// Owner of the defending fleet, or the planet's owner without a fleet
+    pub defender: Pubkey,
+    pub previous_owner: Pubkey,
+    pub target_x: u16,
+    pub target_y: u16,
+    /// Whether the planet changed its owner
+    pub captured: bool,
+    /// Resources left behind in the debris field at the planet
+    pub debris: Resources,
+}
+
+#[event]
+pub struct BuildingBuilt {
+    pub player: Pubkey,
+    pub x: u16,
+    pub y: u16,
+    pub building_type: BuildingType,
+    pub cost: Resources,
+}
+
+#[event]
+pub struct BuildingUpgraded {
+    pub player: Pubkey,
+    pub x: u16,
+    pub y: u16,
+    pub building_type: BuildingType,
+    pub level: u8,
+    pub cost: Resources,
+}
+
+#[event]
+pub struct BuildingChanged {
+    pub player: Pubkey,
+    pub x: u16,
+    pub y: u16,
+    pub building_type_from: BuildingType,
+    pub building_type_to: BuildingType,
+    pub level: u8,
+    pub cost: Resources,
+}
+
+#[event]
+pub struct BuildingRepaired {
+    pub player: Pubkey,
+    pub x: u16,
+    pub y: u16,
+    pub building_type: BuildingType,
+    pub level: u8,
+    pub cost: Resources,
+}
+
+/// Fleet
+#[event]
+pub struct ShipDesignSaved {
+    pub owner: Pubkey,
+    pub index: u16,
+    pub name: String,
+}
+
+#[event]
+pub struct FleetCreated {
+    pub owner: Pubkey,
+    pub x: u16,
+    pub y: u16,
+    pub cost: Resources,
+}
+
+#[event]
+pub struct FleetRefitted {
+    pub owner: Pubkey,
+    pub x: u16,
+    pub y: u16,
+    pub squadron: u8,
+    pub cost: Resources,
+}
+
+#[event]
+pub struct FleetMoved {
+    pub owner: Pubkey,
+    pub x: u16,
+    pub y: u16,
+    pub move_to_x: u16,
+    pub move_to_y: u16,
+    pub fuel: u64,
+    pub arrival_slot: u64,
+}
+
+#[event]
+pub struct FleetSplitOff {
+    pub owner: Pubkey,
+    pub x: u16,
+    pub y: u16,
+    pub to_x: u16,
+    pub to_y: u16,
+    pub fuel: u64,
+}
+
+#[event]
+pub struct FleetMerged {
+    pub owner: Pubkey,
+    pub x: u16,
+    pub y: u16,
+    pub from_x: u16,
+    pub from_y: u16,
+    pub fuel: u64,
+}
+
+#[event]
+pub struct FleetSalvaged {
+    pub owner: Pubkey,
+    pub x: u16,
+    pub y: u16,
+    pub debris_x: u16,
+    pub debris_y: u16,
+    pub resources: Resources,
+}
+
+#[event]
+pub struct FleetDisbanded {
+    pub owner: Pubkey,
+    pub x: u16,
+    pub y: u16,
+    /// Resources credited to the player cache
+    pub refund: Resources,
+}
+
+#[event]
+pub struct FleetArrived {
+    pub owner: Pubkey,
+    pub x: u16,
+    pub y: u16,
+    pub to_x: u16,
+    pub to_y: u16,
+    /// False when the destination got occupied in the meantime and the fleet turned back
+    pub landed: bool,
+}
+
+#[event]
+pub struct FleetAttacked {
+    pub battle_id: u64,
+    pub attacker: Pubkey,
+    pub defender: Pubkey,
+    pub x: u16,
+    pub y: u16,
+    pub target_x: u16,
+    pub target_y: u16,
+    pub winner: BattleSide,
+    pub att_losses: Resources,
+    pub def_losses: Resources,
+    /// Resources credited to the winner
+    pub reward: Resources,
+    pub retreat_x: u16,
+    pub retreat_y: u16,
+    /// Defender's survivors fell back to (retreat_x, retreat_y)
+    pub retreated: bool,
+    /// Attacker moved into the target position
+    pub advanced: bool,
+    /// Resources the attacker's hauling bays took from the defender's planet
+    pub plunder: Resources,
+    /// Resources left behind in the debris field at the target
+    pub debris: Resources,
+}
+
+#[event]
+pub struct PlanetBombarded {
+    pub battle_id: u64,
+    pub attacker: Pubkey,
+    pub defender: Pubkey,
+    pub x: u16,
+    pub y: u16,
+    pub building_type: BuildingType,
+    pub levels_lost: u8,
+}
+
+/// Market pool
+#[event]
+pub struct MarketPoolFunded {
+    pub payer: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64,
+    /// Pool's balance of the asset after the deposit
+    pub pool_amount: u64,
+    /// Nominal amount of IGT for one nominal unit of the asset in the pool after the deposit
+    pub price: f32,
+}
+
+#[event]
+pub struct MarketPoolMinted {
+    pub mint: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct MarketPoolSwapped {
+    pub payer: Pubkey,
+    pub pay_mint: Pubkey,
+    pub pay_amount: u64,
+    pub receive_mint: Pubkey,
+    pub receive_amount: u64,
+    /// Nominal amount of the received asset for one nominal unit of the paid asset
+    pub price: f32,
+}
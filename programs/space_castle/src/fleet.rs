@@ -1,15 +1,19 @@
 use crate::{
+    balance::{BalanceValues, HULL_CLASSES, SHIP_MODULE_TYPES},
     battle::{BattlePresence, Defenses, FleetBattleRound, FleetStats, Morale, Weapons},
     building::{Building, BuildingType},
     mint_decimals,
     resource::Resources,
-    utilities::{calculate_upgrade_cost, convert_from_float},
+    seeds,
+    utilities::{calculate_upgrade_cost_units, convert_from_float},
 };
 use anchor_lang::prelude::*;
-use std::ops::{Div, Mul};
+use std::ops::Mul;
 
 pub const SQUADRONS_IN_FLEET: usize = 9;
 pub const MODULES_ON_SHIP: usize = 6;
+/// Squadrons keep their morale while at least this percent of their health remains
+pub const MORALE_HOLDS_PERCENT: u128 = 60;
 
 #[account]
 #[derive(InitSpace)]
@@ -18,6 +22,23 @@ pub struct Fleet {
     pub owner: Pubkey,
     is_present: bool,
     squadrons: [Option<Squadron>; SQUADRONS_IN_FLEET],
+    /// Set while the fleet travels to another position
+    pub transit: Option<FleetTransit>,
+    /// Slot of the last battle the fleet fought, morale recovers counting from it
+    pub last_battle_slot: u64,
+    /// Position is kept free until this slot for a fleet travelling to it
+    pub reserved_until: u64,
+    /// Slot the fleet last salvaged debris at, its hauling bays need time to unload
+    pub last_salvage_slot: u64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, InitSpace)]
+/// Fleet that left its position and is on its way to (to_x, to_y)
+pub struct FleetTransit {
+    pub to_x: u16,
+    pub to_y: u16,
+    pub departure_slot: u64,
+    pub arrival_slot: u64,
 }
 
 impl Fleet {
@@ -35,24 +56,16 @@ impl Fleet {
         self.owner = owner
     }
 
-    pub fn can_be_built(&self, holding_buildings: [Building; 6]) -> Result<()> {
-        for squadron in self.squadrons.iter().filter_map(|s| s.as_ref()) {
-            ship_can_be_built(squadron.template, holding_buildings)?;
-        }
-        Ok(())
-    }
-
-    pub fn get_quote(&self) -> Resources {
+    pub fn get_quote(&self, balance: &BalanceValues) -> Resources {
         let mut quote = Resources::default();
         for squadron in self.squadrons.iter().filter_map(|s| s.as_ref()) {
-            quote = quote.sum(ship_quote(&squadron.template, squadron.amount))
+            quote = quote.sum(ship_quote(&squadron.template, squadron.amount, balance))
         }
         quote
     }
 
-    pub fn get_move_quote(&self, (x_from, y_from): (u16, u16), (x_to, y_to): (u16, u16)) -> u64 {
-        let distance =
-            (((x_to - x_from).saturating_pow(2) + (y_to - y_from).saturating_pow(2)) as f32).sqrt();
+    pub fn get_move_quote(&self, from: (u16, u16), to: (u16, u16)) -> u64 {
+        let distance = distance(from, to);
         let mut quote = 0u64;
         for squadron in self.squadrons.into_iter().flatten() {
             quote += (ship_move_quote(&squadron.template) as f32).mul(distance) as u64;
@@ -60,6 +73,51 @@ impl Fleet {
         quote
     }
 
+    /// Slots the fleet needs to travel from one position to another, the slowest module in the
+    /// fleet sets the pace
+    pub fn get_travel_slots(
+        &self,
+        from: (u16, u16),
+        to: (u16, u16),
+        balance: &BalanceValues,
+    ) -> u64 {
+        let slowest = self
+            .squadrons
+            .iter()
+            .flatten()
+            .flat_map(|s| s.template.modules.iter())
+            .map(|m| balance.module_travel_slots(m.module_type))
+            .max()
+            .unwrap_or(0);
+        (distance(from, to) * slowest as f32).ceil() as u64
+    }
+
+    /// Sends the fleet on its way, it stays at its current position until it arrives
+    pub fn depart(&mut self, to: (u16, u16), departure_slot: u64, travel_slots: u64) {
+        self.transit = Some(FleetTransit {
+            to_x: to.0,
+            to_y: to.1,
+            departure_slot,
+            arrival_slot: departure_slot.saturating_add(travel_slots),
+        });
+    }
+
+    /// Checks if the fleet is travelling to another position
+    pub fn is_in_transit(&self) -> bool {
+        self.transit.is_some()
+    }
+
+    /// Keeps the position free for a fleet arriving at `arrival_slot`
+    pub fn reserve(&mut self, arrival_slot: u64) {
+        self.reserved_until = self.reserved_until.max(arrival_slot);
+    }
+
+    /// Checks if a fleet can move into the position at `slot`, it has to be empty and not
+    /// reserved for another travelling fleet
+    pub fn is_free(&self, slot: u64) -> bool {
+        !self.is_present && slot >= self.reserved_until
+    }
+
     /// Sets new owner
     pub fn set_presence(&mut self, new_owner: Pubkey) {
         self.owner = new_owner;
@@ -71,6 +129,75 @@ impl Fleet {
         self.is_present = false;
         self.owner = Pubkey::default();
         self.squadrons = [None; SQUADRONS_IN_FLEET];
+        self.transit = None;
+        self.last_battle_slot = 0;
+        self.last_salvage_slot = 0;
+    }
+
+    /// Removes destroyed squadrons after a battle at `slot`, a fleet without ships is reset
+    pub fn settle_after_battle(&mut self, slot: u64) {
+        for squadron in self.squadrons.iter_mut() {
+            if squadron.is_some_and(|s| s.amount == 0) {
+                *squadron = None;
+            }
+        }
+        if self.squadrons.iter().all(|s| s.is_none()) {
+            self.reset();
+            return;
+        }
+        self.last_battle_slot = slot;
+    }
+
+    /// Checks if the fleet's hauling bays are ready to salvage again at `slot`
+    pub fn can_salvage(&self, slot: u64, cooldown_slots: u64) -> bool {
+        self.last_salvage_slot == 0 || slot >= self.last_salvage_slot.saturating_add(cooldown_slots)
+    }
+
+    /// Broken and retreating squadrons regroup once `recovery_slots` passed since the last
+    /// battle
+    pub fn recover_morale(&mut self, slot: u64, recovery_slots: u64) {
+        if slot < self.last_battle_slot.saturating_add(recovery_slots) {
+            return;
+        }
+        for squadron in self.squadrons.iter_mut().flatten() {
+            squadron.morale = Morale::Normal;
+            squadron.presence = BattlePresence::Active;
+        }
+    }
+
+    /// Survivors of a lost battle fall back into `retreat` at `slot`, which has to be free or
+    /// held by the same owner with room for all squadrons
+    ///
+    /// # Returns
+    ///
+    /// * Whether the fleet retreated, a blocked retreat leaves the fleet where it is
+    ///
+    pub fn retreat_into(&mut self, retreat: &mut Fleet, slot: u64) -> bool {
+        if !self.is_present {
+            return false;
+        }
+        if !retreat.is_present {
+            if !retreat.is_free(slot) {
+                return false;
+            }
+            retreat.replace_with_another_fleet(self);
+            self.reset();
+            return true;
+        }
+        // A travelling fleet can't be merged into another one without losing its destination
+        if !retreat.is_owned_by(&self.owner) || retreat.is_in_transit() || self.is_in_transit() {
+            return false;
+        }
+        let mut merged = retreat.clone();
+        if merged.absorb(self.squadrons).is_err() {
+            return false;
+        }
+        merged.last_battle_slot = merged.last_battle_slot.max(self.last_battle_slot);
+        retreat.squadrons = merged.squadrons;
+        retreat.last_battle_slot = merged.last_battle_slot;
+        retreat.last_salvage_slot = retreat.last_salvage_slot.max(self.last_salvage_slot);
+        self.reset();
+        true
     }
 
     /// Checks if the fleet is there, used for determining whether PDA on x,y is
@@ -79,16 +206,25 @@ impl Fleet {
         self.is_present
     }
 
+    /// Player defending the fleet's position, the planet's owner when no fleet is there
+    pub fn defender_or(&self, planet_owner: Pubkey) -> Pubkey {
+        if self.is_present {
+            self.owner
+        } else {
+            planet_owner
+        }
+    }
+
     /// Checks if the fleet has a specific owner
     pub fn is_owned_by(&self, owner: &Pubkey) -> bool {
         self.owner.eq(owner)
     }
 
     /// Gets fleet's battle stats
-    pub fn get_battle_strength(&self) -> FleetStats {
+    pub fn get_battle_strength(&self, balance: &BalanceValues) -> FleetStats {
         let mut fleet_stats = FleetStats::default();
         for squadron in self.squadrons.into_iter().flatten() {
-            let squadron_stats = FleetStats::from_modules(&squadron.template);
+            let squadron_stats = FleetStats::from_template(&squadron.template, balance);
             let Defenses {
                 armor,
                 shield,
@@ -110,7 +246,7 @@ impl Fleet {
     }
 
     /// Takes losses and returns morale adjustment
-    pub fn take_losses(&mut self, attack: &Weapons) -> FleetBattleRound {
+    pub fn take_losses(&mut self, attack: &Weapons, balance: &BalanceValues) -> FleetBattleRound {
         let mut losses = [0u16; SQUADRONS_IN_FLEET];
         let mut morale = [Morale::Broken; SQUADRONS_IN_FLEET];
         let mut presence = [BattlePresence::Gone; SQUADRONS_IN_FLEET];
@@ -124,15 +260,23 @@ impl Fleet {
                 }
             }
         }
+        // Nobody left to take the hits
+        if active_squadrons == 0 {
+            return FleetBattleRound {
+                losses,
+                morale,
+                presence,
+            };
+        }
         // Calc how much dmg per squadron
-        let dmg = attack.divide(active_squadrons); // <---- DIV BY ZERO?
+        let dmg = attack.divide(active_squadrons);
         for (index, squadron) in self.squadrons.iter_mut().enumerate() {
             if let Some(squadron) = squadron {
                 if squadron.presence.eq(&BattlePresence::Gone) {
                     continue;
                 }
                 let is_retreating = squadron.morale.eq(&Morale::Broken);
-                let (loss, new_morale) = squadron.take_damage(&dmg); // <---- DIV BY ZERO?
+                let (loss, new_morale) = squadron.take_damage(&dmg, balance);
                 morale[index] = new_morale;
                 losses[index] += loss;
                 presence[index] = if is_retreating || squadron.amount == 0 {
@@ -142,8 +286,6 @@ impl Fleet {
                 } else {
                     BattlePresence::Active
                 };
-                squadron.morale = new_morale;
-                squadron.amount = squadron.amount.saturating_sub(loss);
                 squadron.presence = presence[index];
             }
         }
@@ -154,6 +296,55 @@ impl Fleet {
         }
     }
 
+    /// Number of landing pods across all ships in the fleet
+    pub fn landing_pods(&self) -> u32 {
+        self.squadrons
+            .iter()
+            .flatten()
+            .map(|s| s.landing_pods_per_ship() as u32 * s.amount as u32)
+            .sum()
+    }
+
+    /// Resources all ships in the fleet can carry in their hauling bays
+    pub fn cargo_capacity(&self, balance: &BalanceValues) -> u64 {
+        self.squadrons
+            .iter()
+            .flatten()
+            .map(|s| {
+                s.cargo_capacity_per_ship(balance)
+                    .saturating_mul(s.amount as u64)
+            })
+            .fold(0, u64::saturating_add)
+    }
+
+    /// Lands ships carrying landing pods until `pods` pods were used up. Ships that landed
+    /// are removed from the fleet and the fleet is reset if no ships are left.
+    pub fn use_landing_pods(&mut self, pods: u16) -> Result<()> {
+        if self.landing_pods() < pods as u32 {
+            return Err(FleetErrorCode::NotEnoughLandingPods.into());
+        }
+        let mut remaining = pods;
+        for slot in self.squadrons.iter_mut() {
+            if let Some(squadron) = slot {
+                let pods_per_ship = squadron.landing_pods_per_ship();
+                if pods_per_ship == 0 {
+                    continue;
+                }
+                while remaining > 0 && squadron.amount > 0 {
+                    squadron.amount -= 1;
+                    remaining = remaining.saturating_sub(pods_per_ship);
+                }
+                if squadron.amount == 0 {
+                    *slot = None;
+                }
+            }
+        }
+        if self.squadrons.iter().all(|s| s.is_none()) {
+            self.reset();
+        }
+        Ok(())
+    }
+
     pub fn in_retreat(&self) -> bool {
         self.squadrons
             .iter()
@@ -163,108 +354,222 @@ impl Fleet {
             == 0
     }
 
-    pub fn build_from_template(
+    /// Fleet that is not stored anywhere, used to simulate battles off-chain
+    pub fn from_blueprints(
+        owner: Pubkey,
+        blueprints: [Option<SquadronBlueprint>; SQUADRONS_IN_FLEET],
+    ) -> Result<Fleet> {
+        let mut fleet = Fleet {
+            owner,
+            is_present: false,
+            squadrons: [None; SQUADRONS_IN_FLEET],
+            transit: None,
+            last_battle_slot: 0,
+            reserved_until: 0,
+            last_salvage_slot: 0,
+        };
+        fleet.reinforce(owner, blueprints)?;
+        Ok(fleet)
+    }
+
+    /// Adds newly built squadrons to the fleet. An empty fleet is created from scratch, an
+    /// existing one is reinforced.
+    pub fn reinforce(
         &mut self,
+        owner: Pubkey,
         fleet_template: [Option<SquadronBlueprint>; SQUADRONS_IN_FLEET],
-    ) {
-        let mut full_squadrons = [None; SQUADRONS_IN_FLEET];
-        for (index, template_squadron) in fleet_template.iter().enumerate() {
-            if let Some(squadron) = template_squadron {
-                full_squadrons[index] = Some(Squadron {
-                    amount: squadron.amount,
-                    template: squadron.template,
-                    presence: BattlePresence::Active,
-                    morale: Morale::Normal,
-                })
+    ) -> Result<()> {
+        if !self.is_present {
+            self.squadrons = [None; SQUADRONS_IN_FLEET];
+            self.set_presence(owner);
+        } else if !self.is_owned_by(&owner) {
+            return Err(FleetErrorCode::ForeignFleet.into());
+        } else if self.is_in_transit() {
+            return Err(FleetErrorCode::FleetInTransit.into());
+        }
+        for blueprint in fleet_template.into_iter().flatten() {
+            if blueprint.amount == 0 {
+                continue;
+            }
+            self.add_squadron(Squadron::from_blueprint(blueprint))?;
+        }
+        Ok(())
+    }
+
+    /// Merges the squadron into a squadron with the same template or puts it into an empty
+    /// slot
+    pub fn add_squadron(&mut self, squadron: Squadron) -> Result<()> {
+        let same_template = self.squadrons.iter_mut().flatten().find(|s| {
+            s.template == squadron.template && s.amount.checked_add(squadron.amount).is_some()
+        });
+        if let Some(existing) = same_template {
+            existing.amount += squadron.amount;
+            return Ok(());
+        }
+        match self.squadrons.iter_mut().find(|s| s.is_none()) {
+            Some(slot) => {
+                *slot = Some(squadron);
+                Ok(())
             }
+            None => Err(FleetErrorCode::FleetFull.into()),
         }
-        self.squadrons = full_squadrons
     }
 
+    /// Takes `amounts[i]` ships out of the squadron in slot `i` and returns them as a new set of
+    /// squadrons. At least one ship has to stay behind.
+    pub fn split_off(
+        &mut self,
+        amounts: [u16; SQUADRONS_IN_FLEET],
+    ) -> Result<[Option<Squadron>; SQUADRONS_IN_FLEET]> {
+        let mut split = [None; SQUADRONS_IN_FLEET];
+        for (index, amount) in amounts.into_iter().enumerate() {
+            if amount == 0 {
+                continue;
+            }
+            let slot = &mut self.squadrons[index];
+            let squadron = slot
+                .as_mut()
+                .filter(|s| s.amount >= amount)
+                .ok_or(FleetErrorCode::NotEnoughShips)?;
+            squadron.amount -= amount;
+            split[index] = Some(Squadron {
+                amount,
+                ..*squadron
+            });
+            if squadron.amount == 0 {
+                *slot = None;
+            }
+        }
+        if split.iter().all(|s| s.is_none()) {
+            return Err(FleetErrorCode::NotEnoughShips.into());
+        }
+        if self.squadrons.iter().all(|s| s.is_none()) {
+            return Err(FleetErrorCode::CannotSplitWholeFleet.into());
+        }
+        Ok(split)
+    }
+
+    /// Takes over all squadrons of another fleet, squadrons with matching templates are combined
+    pub fn absorb(&mut self, squadrons: [Option<Squadron>; SQUADRONS_IN_FLEET]) -> Result<()> {
+        for squadron in squadrons.into_iter().flatten() {
+            self.add_squadron(squadron)?;
+        }
+        Ok(())
+    }
+
+    /// Replaces the ship template of the squadron in slot `index`
+    ///
+    /// # Returns
+    ///
+    /// * The previous template and the number of refitted ships
+    ///
+    pub fn refit(&mut self, index: usize, template: ShipTemplate) -> Result<(ShipTemplate, u16)> {
+        let squadron = self
+            .squadrons
+            .get_mut(index)
+            .and_then(|s| s.as_mut())
+            .ok_or(FleetErrorCode::SquadronNotPresent)?;
+        let previous = squadron.template;
+        squadron.template = template;
+        Ok((previous, squadron.amount))
+    }
+
+    pub fn squadrons(&self) -> [Option<Squadron>; SQUADRONS_IN_FLEET] {
+        self.squadrons
+    }
+
+    /// Takes over `new_fleet`, a travelling fleet keeps heading to the destination reserved
+    /// for it
     pub fn replace_with_another_fleet(&mut self, new_fleet: &Fleet) {
         self.owner = new_fleet.owner;
         self.squadrons = new_fleet.squadrons;
         self.is_present = new_fleet.is_present;
+        self.transit = new_fleet.transit;
+        self.last_battle_slot = new_fleet.last_battle_slot;
+        self.last_salvage_slot = new_fleet.last_salvage_slot;
     }
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, InitSpace, Default)]
 pub struct SquadronBlueprint {
-    template: [ShipModule; MODULES_ON_SHIP], // Aka ShipTemplate
+    template: ShipTemplate,
     amount: u16,
 }
 
+impl SquadronBlueprint {
+    pub fn new(template: ShipTemplate, amount: u16) -> Self {
+        Self { template, amount }
+    }
+
+    pub fn from_design(design: &ShipDesign, amount: u16) -> Self {
+        Self {
+            template: design.template,
+            amount,
+        }
+    }
+
+    pub fn can_be_built(&self, holding_buildings: [Building; 6]) -> Result<()> {
+        ship_can_be_built(self.template, holding_buildings)
+    }
+
+    pub fn get_quote(&self, balance: &BalanceValues) -> Resources {
+        ship_quote(&self.template, self.amount, balance)
+    }
+}
+
+/// Squadron built from a saved `ShipDesign`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, InitSpace, Default)]
+pub struct DesignedSquadron {
+    pub design: u16,
+    pub amount: u16,
+}
+
+#[account]
+#[derive(InitSpace)]
+/// Ship template saved by a player, fleets can be built from it by its index
+pub struct ShipDesign {
+    pub owner: Pubkey,
+    pub index: u16,
+    #[max_len(32)]
+    pub name: String,
+    pub template: ShipTemplate,
+}
+
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, InitSpace, Default)]
 pub struct Squadron {
-    template: [ShipModule; MODULES_ON_SHIP], // Aka ShipTemplate
+    template: ShipTemplate,
     amount: u16,
     morale: Morale,
     presence: BattlePresence,
 }
 
 impl Squadron {
-    pub fn take_damage(&mut self, weapons: &Weapons) -> (u16, Morale) {
-        let fleet_stats = FleetStats::from_modules(&self.template);
-        let Defenses {
-            armor,
-            shield,
-            hull,
-        } = fleet_stats.defenses.multiply(self.amount);
-        let total_health: i128 = armor as i128 + shield as i128 + hull as i128;
-        let mut shield = shield as i128;
-        let mut armor = armor as i128;
-        let mut hull = hull as i128;
-        let mut overflow: i128 = 0;
-
-        // Calcualte damage with overflow
-        for (index, base_damage) in [weapons.laser, weapons.kinetic, weapons.explosive]
-            .iter()
-            .enumerate()
-        {
-            if shield > 0 {
-                shield = shield.saturating_sub(with_modifier(*base_damage, index as u8, 0) as i128);
-                if shield >= 0 {
-                    overflow = 0;
-                    continue;
-                } else {
-                    overflow = without_modifier(shield, index as u8, 0);
-                }
-            }
-            if armor > 0 {
-                armor = armor.saturating_sub(overflow.saturating_add(with_modifier(
-                    *base_damage,
-                    index as u8,
-                    1,
-                ) as i128));
-                if armor >= 0 {
-                    overflow = 0;
-                    continue;
-                } else {
-                    overflow = without_modifier(armor, index as u8, 1);
-                }
-            }
-            if hull > 0 {
-                hull = hull.saturating_sub(overflow.saturating_add(with_modifier(
-                    *base_damage,
-                    index as u8,
-                    2,
-                ) as i128));
-                if hull >= 0 {
-                    overflow = 0;
-                    continue;
-                } else {
-                    overflow = without_modifier(hull, index as u8, 2);
-                }
-            }
+    /// Applies damage to the squadron, removes destroyed ships and returns the losses with
+    /// the new morale. Integer math only so results match off-chain simulations exactly.
+    pub fn take_damage(&mut self, weapons: &Weapons, balance: &BalanceValues) -> (u16, Morale) {
+        let bonus = balance.weapon_surface_bonus_percent;
+        let fleet_stats = FleetStats::from_template(&self.template, balance);
+        let mut defenses = fleet_stats.defenses.multiply(self.amount);
+        let total_health = defenses.total();
+        // Nothing left to hit or nothing to hit with
+        if self.amount == 0 || total_health == 0 {
+            let losses = self.amount;
+            self.amount = 0;
+            self.morale = Morale::Broken;
+            return (losses, Morale::Broken);
         }
-        if hull <= 0 {
-            return (0, Morale::Broken);
+        defenses.take_hits(weapons, bonus);
+        if defenses.hull == 0 {
+            let losses = self.amount;
+            self.amount = 0;
+            self.morale = Morale::Broken;
+            return (losses, Morale::Broken);
         };
-        let remainder_health = shield.saturating_add(armor).saturating_add(hull);
-        let ratio_of_surviving_ships: f32 = (remainder_health as f32).div(total_health as f32); // <---- DIV BY ZERO?
-        let new_amount = (self.amount as f32).mul(ratio_of_surviving_ships) as u16;
-        let losses = self.amount.saturating_sub(new_amount);
-        let morale = if ratio_of_surviving_ships >= 0.6 {
+        let remainder_health = defenses.total();
+        // total_health > 0 was checked above and remainder_health <= total_health
+        let new_amount = (self.amount as u128 * remainder_health / total_health) as u16;
+        let losses = self.amount - new_amount;
+        let morale = if remainder_health * 100 >= total_health * MORALE_HOLDS_PERCENT {
             Morale::Normal
         } else {
             Morale::Broken
@@ -273,6 +578,28 @@ impl Squadron {
         self.amount = new_amount;
         (losses, morale)
     }
+    pub fn amount(&self) -> u16 {
+        self.amount
+    }
+    pub fn template(&self) -> ShipTemplate {
+        self.template
+    }
+    /// Number of `LandingPods` modules on each ship of the squadron
+    pub fn landing_pods_per_ship(&self) -> u16 {
+        self.template
+            .modules
+            .iter()
+            .filter(|m| m.module_type.eq(&ShipModuleType::LandingPods))
+            .count() as u16
+    }
+    /// Resources each ship of the squadron carries, every `HaulingBay` level adds capacity
+    pub fn cargo_capacity_per_ship(&self, balance: &BalanceValues) -> u64 {
+        self.template
+            .mounted()
+            .filter(|m| m.module_type.eq(&ShipModuleType::HaulingBay))
+            .map(|m| balance.hauling_bay_capacity.saturating_mul(m.level as u64))
+            .fold(0, u64::saturating_add)
+    }
     pub fn from_blueprint(blueprint: SquadronBlueprint) -> Self {
         Self {
             template: blueprint.template,
@@ -283,66 +610,184 @@ impl Squadron {
     }
 }
 
-const WEAPON_SURFACE_BONUS: f32 = 1.25;
+/// Checks if two different positions touch, diagonals included
+pub fn is_adjacent((x_from, y_from): (u16, u16), (x_to, y_to): (u16, u16)) -> bool {
+    let dx = x_to.abs_diff(x_from);
+    let dy = y_to.abs_diff(y_from);
+    dx <= 1 && dy <= 1 && dx + dy > 0
+}
 
-/// Adds modifier of a weapons on a surface to damage
-///
-/// weapons: 0 - laser, 1 - kinetic, 2 - explosive
-/// surface: 0 - shield, 1 - armor, 2 - hull
-fn with_modifier(damage: u64, weapon: u8, surface: u8) -> u64 {
-    if weapon == surface {
-        (damage as f32).mul(WEAPON_SURFACE_BONUS) as u64
-    } else {
-        damage
-    }
+/// Positions next to `target` in a fixed order, starting with the one straight away from the
+/// attacker at `from` and going around clockwise. The attacker's own position is left out.
+pub fn retreat_candidates((x_from, y_from): (u16, u16), (x, y): (u16, u16)) -> Vec<(u16, u16)> {
+    const AROUND: [(i32, i32); 8] = [
+        (0, -1),
+        (1, -1),
+        (1, 0),
+        (1, 1),
+        (0, 1),
+        (-1, 1),
+        (-1, 0),
+        (-1, -1),
+    ];
+    let away = (x as i32 - x_from as i32, y as i32 - y_from as i32);
+    let start = AROUND.iter().position(|d| *d == away).unwrap_or(0);
+    (0..AROUND.len())
+        .map(|i| AROUND[(start + i) % AROUND.len()])
+        .filter_map(|(dx, dy)| {
+            let x = u16::try_from(x as i32 + dx).ok()?;
+            let y = u16::try_from(y as i32 + dy).ok()?;
+            Some((x, y))
+        })
+        .filter(|position| *position != (x_from, y_from))
+        .collect()
 }
 
-/// Remove modifier of a weapons on a surface damage
+/// Falls back into the first free position around the target at `slot` when the `retreat`
+/// given by the attacker at `from` was blocked, in the order of `retreat_candidates`.
+/// `accounts` hold the fleet accounts of those positions in the same order, positions that
+/// never had a fleet have no account and are skipped.
+///
+/// # Returns
+///
+/// * Position the fleet retreated to, if any
 ///
-/// weapons: 0 - laser, 1 - kinetic, 2 - explosive
-/// surface: 0 - shield, 1 - armor, 2 - hull
-fn without_modifier(damage: i128, weapon: u8, surface: u8) -> i128 {
-    if weapon == surface {
-        (damage as f32).mul(1.0 / WEAPON_SURFACE_BONUS) as i128
-    } else {
-        damage
+pub fn retreat_into_fallback(
+    fleet: &mut Fleet,
+    (from, target, retreat): ((u16, u16), (u16, u16), (u16, u16)),
+    accounts: &[AccountInfo],
+    slot: u64,
+) -> Result<Option<(u16, u16)>> {
+    if !fleet.is_present() {
+        return Ok(None);
+    }
+    let candidates = retreat_candidates(from, target)
+        .into_iter()
+        .filter(|position| *position != retreat);
+    for ((x, y), account) in candidates.zip(accounts) {
+        let (key, _) = Pubkey::find_program_address(
+            &[
+                seeds::FLEET,
+                x.to_le_bytes().as_ref(),
+                y.to_le_bytes().as_ref(),
+            ],
+            &crate::ID,
+        );
+        if account.key() != key || !account.is_writable {
+            return Err(FleetErrorCode::InvalidRetreat.into());
+        }
+        if account.owner != &crate::ID || account.data_is_empty() {
+            continue;
+        }
+        let mut fallback = Fleet::try_deserialize(&mut &account.data.borrow()[..])?;
+        if fleet.retreat_into(&mut fallback, slot) {
+            fallback.try_serialize(&mut &mut account.try_borrow_mut_data()?[..])?;
+            return Ok(Some((x, y)));
+        }
     }
+    Ok(None)
 }
 
-pub type ShipTemplate = [ShipModule; MODULES_ON_SHIP];
+/// Straight line distance between two positions
+pub fn distance((x_from, y_from): (u16, u16), (x_to, y_to): (u16, u16)) -> f32 {
+    let dx = x_to.abs_diff(x_from) as f32;
+    let dy = y_to.abs_diff(y_from) as f32;
+    (dx * dx + dy * dy).sqrt()
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, InitSpace, Default, PartialEq)]
+#[cfg_attr(feature = "simulator", derive(serde::Serialize, serde::Deserialize))]
+/// Hull of a ship with modules mounted in its slots
+pub struct ShipTemplate {
+    pub hull: HullClass,
+    pub modules: [ShipModule; MODULES_ON_SHIP],
+}
+
+impl ShipTemplate {
+    /// Modules that are actually mounted
+    pub fn mounted(&self) -> impl Iterator<Item = &ShipModule> {
+        self.modules
+            .iter()
+            .filter(|m| !m.module_type.eq(&ShipModuleType::None))
+    }
+}
 
 pub fn starting_ship(affinity: u8) -> ShipTemplate {
-    let mut template = [ShipModule::default(); MODULES_ON_SHIP];
-    template[0] = ShipModule {
+    let mut modules = [ShipModule::default(); MODULES_ON_SHIP];
+    modules[0] = ShipModule {
         module_type: ShipModuleType::MiningDrill,
         level: 1,
     };
-    template[1] = ShipModule {
+    modules[1] = ShipModule {
         module_type: ShipModuleType::weapon_from_affinity(affinity),
         level: 1,
     };
-    template
+    ShipTemplate {
+        hull: HullClass::Frigate,
+        modules,
+    }
 }
-pub fn ship_can_be_built(template: ShipTemplate, holding_buildings: [Building; 6]) -> Result<()> {
-    for module in template
+/// Checks that the template describes an actual ship that follows the rules of its hull
+///
+/// * at least one module and no modules below level 1
+/// * no modules past the hull's slots
+/// * only module categories that the hull allows
+/// * at most `MAX_SUPPORT_MODULES` support modules
+/// * armed hulls carry at least one weapon
+pub fn validate_ship_template(template: &ShipTemplate) -> Result<()> {
+    let hull = template.hull;
+    if template.modules[hull.slots()..]
         .iter()
-        .filter(|m| !m.module_type.eq(&ShipModuleType::None))
+        .any(|m| !m.module_type.eq(&ShipModuleType::None))
     {
+        return Err(FleetErrorCode::HullSlotsExceeded.into());
+    }
+    let mut count = 0;
+    let mut weapons = 0;
+    let mut support = 0;
+    for module in template.mounted() {
+        if module.level == 0 {
+            return Err(FleetErrorCode::InvalidShipTemplate.into());
+        }
+        let category = module.module_type.category();
+        if !hull.allows(category) {
+            return Err(FleetErrorCode::ModuleNotAllowedOnHull.into());
+        }
+        match category {
+            ModuleCategory::Weapon => weapons += 1,
+            ModuleCategory::Support => support += 1,
+            _ => {}
+        }
+        count += 1;
+    }
+    if count == 0 {
+        return Err(FleetErrorCode::InvalidShipTemplate.into());
+    }
+    if support > MAX_SUPPORT_MODULES {
+        return Err(FleetErrorCode::TooManySupportModules.into());
+    }
+    if hull.is_armed() && weapons == 0 {
+        return Err(FleetErrorCode::ShipNeedsWeapon.into());
+    }
+    Ok(())
+}
+pub fn ship_can_be_built(template: ShipTemplate, holding_buildings: [Building; 6]) -> Result<()> {
+    validate_ship_template(&template)?;
+    for module in template.mounted() {
         // Check if the module can be built
-        module.module_type.can_be_built(holding_buildings)?;
+        module
+            .module_type
+            .can_be_built(module.level, holding_buildings)?;
     }
     Ok(())
 }
-pub fn ship_quote(template: &ShipTemplate, amount: u16) -> Resources {
-    let mut costs = Resources::default();
-    for module in template
-        .iter()
-        .filter(|m| !m.module_type.eq(&ShipModuleType::None))
-    {
+pub fn ship_quote(template: &ShipTemplate, amount: u16, balance: &BalanceValues) -> Resources {
+    let mut costs = template.hull.get_quote(balance).mul(amount as u64);
+    for module in template.mounted() {
         costs = costs.sum(
             module
                 .module_type
-                .get_quote(module.level)
+                .get_quote(module.level, balance)
                 .mul(amount as u64),
         )
     }
@@ -350,16 +795,14 @@ pub fn ship_quote(template: &ShipTemplate, amount: u16) -> Resources {
 }
 pub fn ship_move_quote(template: &ShipTemplate) -> u64 {
     let mut fuel_cost = 0u64;
-    for module in template
-        .iter()
-        .filter(|m| !m.module_type.eq(&ShipModuleType::None))
-    {
+    for module in template.mounted() {
         fuel_cost = fuel_cost.saturating_add(module.level as u64);
     }
     fuel_cost
 }
+#[allow(dead_code)]
 pub fn default_morale(template: ShipTemplate) -> Morale {
-    match template.iter().any(|m| {
+    match template.modules.iter().any(|m| {
         matches!(
             m.module_type,
             ShipModuleType::Lasers | ShipModuleType::Rockets | ShipModuleType::MachineGun
@@ -370,13 +813,105 @@ pub fn default_morale(template: ShipTemplate) -> Morale {
     }
 }
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, InitSpace, Default)]
+/// Ships can carry only this many support modules
+pub const MAX_SUPPORT_MODULES: u8 = 1;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, InitSpace, Default, PartialEq)]
+#[cfg_attr(feature = "simulator", derive(serde::Serialize, serde::Deserialize))]
+pub enum HullClass {
+    /// Small and cheap, weapons and defenses only
+    Corvette,
+    #[default]
+    Frigate,
+    /// Heavy warship without room for cargo
+    Cruiser,
+    /// Unarmed hull for cargo and landing pods
+    Carrier,
+}
+
+impl HullClass {
+    pub const ALL: [HullClass; HULL_CLASSES] = [
+        HullClass::Corvette,
+        HullClass::Frigate,
+        HullClass::Cruiser,
+        HullClass::Carrier,
+    ];
+
+    /// Number of module slots, modules past it have to be `None`
+    pub fn slots(&self) -> usize {
+        match self {
+            HullClass::Corvette => 2,
+            HullClass::Frigate => 3,
+            HullClass::Cruiser => 5,
+            HullClass::Carrier => 6,
+        }
+    }
+
+    pub fn allows(&self, category: ModuleCategory) -> bool {
+        match self {
+            HullClass::Corvette => {
+                matches!(category, ModuleCategory::Weapon | ModuleCategory::Defense)
+            }
+            HullClass::Frigate => true,
+            HullClass::Cruiser => !matches!(category, ModuleCategory::Cargo),
+            HullClass::Carrier => !matches!(category, ModuleCategory::Weapon),
+        }
+    }
+
+    /// Armed hulls need at least one weapon
+    pub fn is_armed(&self) -> bool {
+        !matches!(self, HullClass::Carrier)
+    }
+
+    pub fn base_defenses(&self) -> Defenses {
+        match self {
+            HullClass::Corvette => Defenses::from_numbers(0, 1, 2),
+            HullClass::Frigate => Defenses::from_numbers(1, 1, 3),
+            HullClass::Cruiser => Defenses::from_numbers(2, 2, 5),
+            HullClass::Carrier => Defenses::from_numbers(2, 2, 6),
+        }
+    }
+
+    pub fn get_quote(&self, balance: &BalanceValues) -> Resources {
+        let [metal, crystal, chemical, fuel] = balance.hull_base_cost(*self);
+        Resources {
+            igt: 0,
+            metal: convert_from_float(metal, mint_decimals::METAL),
+            crystal: convert_from_float(crystal, mint_decimals::CRYSTAL),
+            chemical: convert_from_float(chemical, mint_decimals::CHEMICAL),
+            fuel: convert_from_float(fuel, mint_decimals::FUEL),
+        }
+    }
+
+    /// Base cost the balance config starts with
+    pub fn default_base_cost(&self) -> [f32; 4] {
+        match self {
+            HullClass::Corvette => [1.0, 0.5, 0.5, 0.5],
+            HullClass::Frigate => [2.0, 1.0, 1.0, 1.0],
+            HullClass::Cruiser => [5.0, 2.5, 2.5, 2.0],
+            HullClass::Carrier => [6.0, 2.0, 2.0, 3.0],
+        }
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq)]
+pub enum ModuleCategory {
+    None,
+    Weapon,
+    Defense,
+    Support,
+    Cargo,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, InitSpace, Default, PartialEq)]
+#[cfg_attr(feature = "simulator", derive(serde::Serialize, serde::Deserialize))]
 pub struct ShipModule {
     pub module_type: ShipModuleType,
     pub level: u8,
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, InitSpace, Default, PartialEq)]
+#[cfg_attr(feature = "simulator", derive(serde::Serialize, serde::Deserialize))]
 pub enum ShipModuleType {
     #[default]
     None,
@@ -393,6 +928,20 @@ pub enum ShipModuleType {
 }
 
 impl ShipModuleType {
+    pub const ALL: [ShipModuleType; SHIP_MODULE_TYPES] = [
+        ShipModuleType::None,
+        ShipModuleType::Rockets,
+        ShipModuleType::MachineGun,
+        ShipModuleType::Lasers,
+        ShipModuleType::HardenedHull,
+        ShipModuleType::ShieldBooster,
+        ShipModuleType::AdditionalArmor,
+        ShipModuleType::HaulingBay,
+        ShipModuleType::TargetingSystems,
+        ShipModuleType::MiningDrill,
+        ShipModuleType::LandingPods,
+    ];
+
     /// # Params
     /// * affinity - 0 = metal, 1 = crystal, other = chemical
     pub fn weapon_from_affinity(affinity: u8) -> ShipModuleType {
@@ -403,8 +952,26 @@ impl ShipModuleType {
         }
     }
 
-    pub fn can_be_built(&self, buildings: [Building; 6]) -> Result<()> {
-        let relevant_building: Option<BuildingType> = match self {
+    pub fn category(&self) -> ModuleCategory {
+        match self {
+            ShipModuleType::None => ModuleCategory::None,
+            ShipModuleType::Rockets | ShipModuleType::MachineGun | ShipModuleType::Lasers => {
+                ModuleCategory::Weapon
+            }
+            ShipModuleType::HardenedHull
+            | ShipModuleType::ShieldBooster
+            | ShipModuleType::AdditionalArmor => ModuleCategory::Defense,
+            ShipModuleType::TargetingSystems => ModuleCategory::Support,
+            ShipModuleType::HaulingBay
+            | ShipModuleType::MiningDrill
+            | ShipModuleType::LandingPods => ModuleCategory::Cargo,
+        }
+    }
+
+    /// Building that unlocks the module, its level is the highest level the module can be built
+    /// with
+    pub fn unlocking_building(&self) -> Option<BuildingType> {
+        match self {
             ShipModuleType::None => None,
             ShipModuleType::Rockets => Some(BuildingType::ChemicalRefinery),
             ShipModuleType::MachineGun => Some(BuildingType::MetalIndustry),
@@ -416,39 +983,46 @@ impl ShipModuleType {
             ShipModuleType::TargetingSystems => Some(BuildingType::AstralNavyHQ),
             ShipModuleType::MiningDrill => None,
             ShipModuleType::LandingPods => Some(BuildingType::AstralNavyHQ),
-        };
-        if let Some(relevant_building) = relevant_building {
-            buildings
-                .binary_search_by_key(&relevant_building, |b| b.building_type)
-                .map_err(|_| FleetErrorCode::CantBuildMissingBuilding)?;
+        }
+    }
+
+    pub fn can_be_built(&self, level: u8, buildings: [Building; 6]) -> Result<()> {
+        if let Some(relevant_building) = self.unlocking_building() {
+            let building = buildings
+                .iter()
+                .find(|b| b.building_type == relevant_building)
+                .ok_or(FleetErrorCode::CantBuildMissingBuilding)?;
+            if level > building.level {
+                return Err(FleetErrorCode::ModuleLevelTooHigh.into());
+            }
         }
         Ok(())
     }
 
-    pub fn get_quote(&self, level: u8) -> Resources {
-        let base_cost = self.base_cost();
+    pub fn get_quote(&self, level: u8, balance: &BalanceValues) -> Resources {
+        let base_cost = balance.module_base_cost(*self);
+        let factor = balance.module_upgrade_factor;
         Resources {
-            igt: convert_from_float(self.base_cost_igt(), mint_decimals::IGT),
-            metal: convert_from_float(
-                calculate_upgrade_cost(base_cost[0], 1.6, level),
-                mint_decimals::METAL,
-            ),
-            crystal: convert_from_float(
-                calculate_upgrade_cost(base_cost[1], 1.6, level),
+            igt: convert_from_float(balance.module_base_cost_igt(*self), mint_decimals::IGT),
+            metal: calculate_upgrade_cost_units(base_cost[0], factor, level, mint_decimals::METAL),
+            crystal: calculate_upgrade_cost_units(
+                base_cost[1],
+                factor,
+                level,
                 mint_decimals::CRYSTAL,
             ),
-            chemical: convert_from_float(
-                calculate_upgrade_cost(base_cost[2], 1.6, level),
+            chemical: calculate_upgrade_cost_units(
+                base_cost[2],
+                factor,
+                level,
                 mint_decimals::CHEMICAL,
             ),
-            fuel: convert_from_float(
-                calculate_upgrade_cost(base_cost[3], 1.6, level),
-                mint_decimals::FUEL,
-            ),
+            fuel: calculate_upgrade_cost_units(base_cost[3], factor, level, mint_decimals::FUEL),
         }
     }
 
-    pub fn base_cost(&self) -> [f32; 4] {
+    /// Base cost the balance config starts with
+    pub fn default_base_cost(&self) -> [f32; 4] {
         match self {
             ShipModuleType::None => [0.0, 0.0, 0.0, 0.0],
             ShipModuleType::Rockets => [0.5, 0.5, 2.5, 0.5],
@@ -464,7 +1038,25 @@ impl ShipModuleType {
         }
     }
 
-    pub fn base_cost_igt(&self) -> f32 {
+    /// Slots needed to travel one unit of distance the balance config starts with
+    pub fn default_travel_slots(&self) -> u64 {
+        match self {
+            ShipModuleType::None => 0,
+            ShipModuleType::Rockets => 10,
+            ShipModuleType::MachineGun => 10,
+            ShipModuleType::Lasers => 10,
+            ShipModuleType::HardenedHull => 15,
+            ShipModuleType::ShieldBooster => 10,
+            ShipModuleType::AdditionalArmor => 15,
+            ShipModuleType::HaulingBay => 20,
+            ShipModuleType::TargetingSystems => 10,
+            ShipModuleType::MiningDrill => 15,
+            ShipModuleType::LandingPods => 20,
+        }
+    }
+
+    /// Base IGT cost the balance config starts with
+    pub fn default_base_cost_igt(&self) -> f32 {
         match self {
             ShipModuleType::None => 0.0,
             ShipModuleType::Rockets => 0.001,
@@ -524,4 +1116,177 @@ pub enum FleetErrorCode {
     FleetNotPresent,
     #[msg("Can't move fleet to position, its already occupied")]
     IllegalMoveAlreadyOccupied,
+    #[msg("Fleet does not carry enough landing pods")]
+    NotEnoughLandingPods,
+    #[msg("Not enough resources to build this fleet")]
+    NotEnoughResources,
+    #[msg("Fleet at position is owned by another player")]
+    ForeignFleet,
+    #[msg("Fleet has no free squadron slots left")]
+    FleetFull,
+    #[msg("Fleet is travelling to another position")]
+    FleetInTransit,
+    #[msg("Fleet is not travelling anywhere")]
+    FleetNotInTransit,
+    #[msg("Fleet has not arrived yet")]
+    FleetNotArrived,
+    #[msg("Squadron does not have that many ships")]
+    NotEnoughShips,
+    #[msg("At least one ship has to stay in the fleet")]
+    CannotSplitWholeFleet,
+    #[msg("Fleets can only act on an adjacent position")]
+    NotAdjacent,
+    #[msg("Module level is higher than the level of the building that unlocks it")]
+    ModuleLevelTooHigh,
+    #[msg("No squadron in this slot")]
+    SquadronNotPresent,
+    #[msg("Ship template needs at least one module and every module at least level 1")]
+    InvalidShipTemplate,
+    #[msg("Ship has more modules than its hull has slots")]
+    HullSlotsExceeded,
+    #[msg("Hull does not allow this kind of module")]
+    ModuleNotAllowedOnHull,
+    #[msg("Ship carries too many support modules")]
+    TooManySupportModules,
+    #[msg("Armed hulls need at least one weapon")]
+    ShipNeedsWeapon,
+    #[msg("Ship design name is too long. Limit is 32 characters.")]
+    DesignNameTooLong,
+    #[msg("Ship design does not belong to the player or has a different index")]
+    InvalidShipDesign,
+    #[msg("Retreat has to be adjacent to the battle and away from the attacker")]
+    InvalidRetreat,
+    #[msg("Fleet has no hauling bays")]
+    NoCargoCapacity,
+    #[msg("There is no debris to salvage")]
+    NothingToSalvage,
+    #[msg("Fleet has to wait before it can salvage again")]
+    SalvageOnCooldown,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Corvettes with a machine gun, 10 of them have 10 shield, 20 armor and 30 hull
+    fn corvettes(amount: u16) -> Squadron {
+        let mut modules = [ShipModule::default(); MODULES_ON_SHIP];
+        modules[0] = ShipModule {
+            module_type: ShipModuleType::MachineGun,
+            level: 1,
+        };
+        Squadron::from_blueprint(SquadronBlueprint::new(
+            ShipTemplate {
+                hull: HullClass::Corvette,
+                modules,
+            },
+            amount,
+        ))
+    }
+
+    fn corvette_fleet() -> Fleet {
+        let mut blueprints = [None; SQUADRONS_IN_FLEET];
+        blueprints[0] = Some(SquadronBlueprint::new(corvettes(1).template, 10));
+        Fleet::from_blueprints(Pubkey::new_unique(), blueprints).unwrap()
+    }
+
+    fn empty_fleet() -> Fleet {
+        let mut fleet =
+            Fleet::from_blueprints(Pubkey::default(), [None; SQUADRONS_IN_FLEET]).unwrap();
+        fleet.reset();
+        fleet
+    }
+
+    #[test]
+    fn retreat_respects_a_reserved_position() {
+        let mut fleet = corvette_fleet();
+        let mut retreat = empty_fleet();
+        retreat.reserve(100);
+        assert!(!fleet.retreat_into(&mut retreat, 99));
+        assert!(fleet.is_present());
+        assert!(fleet.retreat_into(&mut retreat, 100));
+        assert!(retreat.is_present() && !fleet.is_present());
+    }
+
+    #[test]
+    fn replaced_fleet_keeps_travelling() {
+        let mut fleet = corvette_fleet();
+        fleet.transit = Some(FleetTransit {
+            to_x: 4,
+            to_y: 4,
+            departure_slot: 10,
+            arrival_slot: 20,
+        });
+        let mut other = empty_fleet();
+        other.replace_with_another_fleet(&fleet);
+        assert!(other.transit.is_some_and(|t| (t.to_x, t.to_y) == (4, 4)));
+    }
+
+    #[test]
+    fn retreat_candidates_start_away_from_the_attacker() {
+        let candidates = retreat_candidates((1, 1), (2, 2));
+        assert_eq!(candidates.len(), 7);
+        assert_eq!(candidates[0], (3, 3));
+        assert_eq!(candidates[1], (2, 3));
+        assert!(!candidates.contains(&(1, 1)));
+        // Positions off the map are left out
+        assert_eq!(retreat_candidates((1, 0), (0, 0)), vec![(1, 1), (0, 1)]);
+    }
+
+    #[test]
+    fn salvage_waits_for_the_cooldown() {
+        let mut fleet =
+            Fleet::from_blueprints(Pubkey::new_unique(), [None; SQUADRONS_IN_FLEET]).unwrap();
+        assert!(fleet.can_salvage(10, 150));
+        fleet.last_salvage_slot = 1000;
+        assert!(!fleet.can_salvage(1149, 150));
+        assert!(fleet.can_salvage(1150, 150));
+    }
+
+    #[test]
+    fn squadron_without_ships_has_no_health_and_breaks() {
+        let mut squadron = corvettes(0);
+        let (losses, morale) =
+            squadron.take_damage(&Weapons::from_numbers(1, 1, 1), &BalanceValues::default());
+        assert_eq!(losses, 0);
+        assert!(morale == Morale::Broken);
+        assert_eq!(squadron.amount(), 0);
+    }
+
+    #[test]
+    fn destroyed_hull_loses_every_ship() {
+        let mut squadron = corvettes(10);
+        let (losses, morale) =
+            squadron.take_damage(&Weapons::from_numbers(0, 0, 100), &BalanceValues::default());
+        assert_eq!(losses, 10);
+        assert!(morale == Morale::Broken);
+        assert_eq!(squadron.amount(), 0);
+    }
+
+    #[test]
+    fn survivors_follow_the_remaining_health() {
+        let mut squadron = corvettes(10);
+        // Shield is gone and 1 armor with it, 49 of 60 health is left
+        let (losses, morale) =
+            squadron.take_damage(&Weapons::from_numbers(10, 0, 0), &BalanceValues::default());
+        assert_eq!(losses, 2);
+        assert!(morale == Morale::Normal);
+        assert_eq!(squadron.amount(), 8);
+    }
+
+    #[test]
+    fn morale_holds_down_to_the_threshold() {
+        let balance = BalanceValues::default();
+        // Lasers take the 10 shield, rockets 14 armor, 36 of 60 health is left
+        let mut squadron = corvettes(10);
+        let (losses, morale) = squadron.take_damage(&Weapons::from_numbers(8, 0, 14), &balance);
+        assert_eq!(losses, 4);
+        assert!(morale == Morale::Normal);
+
+        // One more point of damage breaks it
+        let mut squadron = corvettes(10);
+        let (losses, morale) = squadron.take_damage(&Weapons::from_numbers(8, 0, 15), &balance);
+        assert_eq!(losses, 5);
+        assert!(morale == Morale::Broken);
+    }
 }
@@ -0,0 +1,17 @@
+use anchor_lang::prelude::*;
+
+#[account]
+#[derive(InitSpace)]
+/// Global game settings
+pub struct GameConfig {
+    /// Authority over privileged instructions, like minting new tokens
+    pub admin: Pubkey,
+}
+
+#[error_code]
+pub enum GameErrorCode {
+    #[msg("Signer is not the game admin")]
+    NotAdmin,
+    #[msg("Only the program upgrade authority can initialize the game")]
+    NotUpgradeAuthority,
+}
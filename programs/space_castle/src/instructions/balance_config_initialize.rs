@@ -0,0 +1,38 @@
+use crate::{
+    balance::{BalanceConfig, BalanceValues},
+    event::BalanceConfigUpdated,
+    game::{GameConfig, GameErrorCode},
+    seeds,
+};
+use anchor_lang::prelude::*;
+
+pub fn balance_config_initialize(ctx: Context<BalanceConfigInitialize>) -> Result<()> {
+    let balance_config = &mut ctx.accounts.balance_config;
+    balance_config.version = 1;
+    balance_config.values = BalanceValues::default();
+    emit!(BalanceConfigUpdated {
+        version: balance_config.version,
+    });
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct BalanceConfigInitialize<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    #[account(
+        seeds = [seeds::GAME],
+        bump,
+        has_one = admin @ GameErrorCode::NotAdmin
+    )]
+    pub game_config: Account<'info, GameConfig>,
+    #[account(
+        init,
+        payer = admin,
+        seeds = [seeds::BALANCE_CONFIG],
+        bump,
+        space = 8 + BalanceConfig::INIT_SPACE
+    )]
+    pub balance_config: Box<Account<'info, BalanceConfig>>,
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,72 @@
+use crate::{
+    balance::{BalanceConfig, BalanceValues},
+    event::BalanceConfigUpdated,
+    game::{GameConfig, GameErrorCode},
+    seeds,
+};
+use anchor_lang::{prelude::*, system_program, Discriminator};
+
+pub fn balance_config_update(
+    ctx: Context<BalanceConfigUpdate>,
+    values: BalanceValues,
+) -> Result<()> {
+    values.validate()?;
+
+    // Config stored by an older program may have fewer values, it is replaced as a whole so
+    // only its version is read
+    let account_info = ctx.accounts.balance_config.to_account_info();
+    let version = {
+        let data = account_info.try_borrow_data()?;
+        if data.len() < 12 || data[..8] != BalanceConfig::DISCRIMINATOR {
+            return Err(ErrorCode::AccountDiscriminatorMismatch.into());
+        }
+        u32::from_le_bytes([data[8], data[9], data[10], data[11]])
+    };
+
+    // Grow the account when the program added new values
+    let new_account_size = 8 + BalanceConfig::INIT_SPACE;
+    if account_info.data_len() < new_account_size {
+        let lamports_required = Rent::get()?.minimum_balance(new_account_size);
+        let additional_rent_to_fund = lamports_required.saturating_sub(account_info.lamports());
+        if additional_rent_to_fund > 0 {
+            system_program::transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: ctx.accounts.admin.to_account_info(),
+                        to: account_info.clone(),
+                    },
+                ),
+                additional_rent_to_fund,
+            )?;
+        }
+        account_info.realloc(new_account_size, false)?;
+    }
+
+    let balance_config = BalanceConfig {
+        version: version.saturating_add(1),
+        values,
+    };
+    balance_config.try_serialize(&mut &mut account_info.try_borrow_mut_data()?[..])?;
+    emit!(BalanceConfigUpdated {
+        version: balance_config.version,
+    });
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct BalanceConfigUpdate<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    #[account(
+        seeds = [seeds::GAME],
+        bump,
+        has_one = admin @ GameErrorCode::NotAdmin
+    )]
+    pub game_config: Account<'info, GameConfig>,
+    /// CHECK: Balance config, read by hand since it may still have the layout of an older
+    /// program
+    #[account(mut, seeds = [seeds::BALANCE_CONFIG], bump, owner = crate::ID)]
+    pub balance_config: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
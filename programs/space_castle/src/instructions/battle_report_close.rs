@@ -0,0 +1,25 @@
+use crate::{battle::BattleReport, seeds};
+use anchor_lang::prelude::*;
+
+pub fn battle_report_close(_ctx: Context<BattleReportClose>, _id: u64) -> Result<()> {
+    // `close` returns the report's rent to the attacker
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(id: u64)]
+pub struct BattleReportClose<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    #[account(
+        mut,
+        close = signer,
+        seeds = [
+            seeds::BATTLE_REPORT,
+            signer.key().as_ref(),
+            id.to_le_bytes().as_ref()
+        ],
+        bump,
+    )]
+    pub battle_report: Box<Account<'info, BattleReport>>,
+}
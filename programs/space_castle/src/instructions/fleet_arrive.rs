@@ -0,0 +1,74 @@
+use crate::{
+    event::FleetArrived,
+    fleet::{Fleet, FleetErrorCode},
+    seeds,
+};
+use anchor_lang::prelude::*;
+
+pub fn fleet_arrive(ctx: Context<FleetArrive>, x: u16, y: u16, to_x: u16, to_y: u16) -> Result<()> {
+    let fleet_from = &mut ctx.accounts.fleet_from;
+    let fleet_to = &mut ctx.accounts.fleet_to;
+    let owner = fleet_from.owner;
+    match fleet_from.transit {
+        Some(transit) if transit.to_x == to_x && transit.to_y == to_y => {
+            if Clock::get()?.slot < transit.arrival_slot {
+                return Err(FleetErrorCode::FleetNotArrived.into());
+            }
+        }
+        _ => return Err(FleetErrorCode::FleetNotInTransit.into()),
+    }
+
+    // Destination was reserved until the arrival slot, another fleet can only be there if a
+    // battle pushed it there or this one wasn't landed in time, then the fleet turns back
+    let landed = !fleet_to.is_present();
+    if landed {
+        fleet_to.replace_with_another_fleet(fleet_from);
+        fleet_to.transit = None;
+        fleet_from.reset();
+    } else {
+        fleet_from.transit = None;
+    }
+    emit!(FleetArrived {
+        owner,
+        x,
+        y,
+        to_x,
+        to_y,
+        landed,
+    });
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(x: u16, y: u16, to_x: u16, to_y: u16)]
+pub struct FleetArrive<'info> {
+    /// Anyone can land an arrived fleet
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    // From
+    #[account(
+        mut,
+        seeds = [
+            seeds::FLEET,
+            x.to_le_bytes().as_ref(),
+            y.to_le_bytes().as_ref(),
+        ],
+        bump,
+        constraint = fleet_from.is_present() @ FleetErrorCode::FleetNotPresent,
+    )]
+    pub fleet_from: Account<'info, Fleet>,
+    // To
+    #[account(
+        init_if_needed,
+        seeds = [
+            seeds::FLEET,
+            to_x.to_le_bytes().as_ref(),
+            to_y.to_le_bytes().as_ref()
+        ],
+        bump,
+        space = 8 + Fleet::INIT_SPACE,
+        payer = signer,
+    )]
+    pub fleet_to: Account<'info, Fleet>,
+    pub system_program: Program<'info, System>,
+}
@@ -1,43 +1,163 @@
 use crate::{
-    battle::{fleet_battle, BattleResult, BattleSide},
-    fleet::{Fleet, FleetErrorCode},
-    resource::{process_burn_resource, PlayerCache, ResourceAuthority},
+    balance::BalanceConfig,
+    battle::{fleet_battle, BattleReport, BattleSide, DebrisField, PlanetDefenses},
+    building::{BuildingErrorCode, BuildingType},
+    event::{FleetAttacked, PlanetBombarded},
+    fleet::{is_adjacent, retreat_into_fallback, Fleet, FleetErrorCode},
+    planet::{get_planet_resources, PlanetHolding},
+    player::Player,
+    resource::{process_burn_resource, PlayerCache, ResourceAuthority, Resources},
+    reward::BattleRewards,
     seeds,
 };
 use anchor_lang::prelude::*;
 use anchor_spl::token::{Mint, Token, TokenAccount};
 
+#[allow(clippy::too_many_arguments)]
 pub fn fleet_attack(
     ctx: Context<FleetAttack>,
     x: u16,
     y: u16,
     target_x: u16,
     target_y: u16,
+    retreat_x: u16,
+    retreat_y: u16,
+    advance: bool,
+    bombard: Option<BuildingType>,
 ) -> Result<()> {
+    // Fleets have to travel next to their target before they can attack it
+    if !is_adjacent((x, y), (target_x, target_y)) {
+        return Err(FleetErrorCode::NotAdjacent.into());
+    }
+    let balance = &ctx.accounts.balance_config.values;
+    let slot = Clock::get()?.slot;
     let fleet = &mut ctx.accounts.fleet;
     let fleet_target = &mut ctx.accounts.fleet_target;
+    let defender = ctx.accounts.defender.key();
+
+    // Defense buildings join in when the defender owns the planet at the target, a planet
+    // can be attacked without a fleet parked on it
+    let mut holding =
+        PlanetHolding::try_from_optional(&ctx.accounts.planet_holding.to_account_info())?;
+    if !fleet_target.is_present() && holding.is_none() {
+        return Err(FleetErrorCode::FleetNotPresent.into());
+    }
+
+    // Squadrons broken in earlier battles may have regrouped by now
+    fleet.recover_morale(slot, balance.morale_recovery_slots);
+    fleet_target.recover_morale(slot, balance.morale_recovery_slots);
+
+    let mut planet = holding
+        .as_ref()
+        .map(|holding| PlanetDefenses::from_buildings(&holding.buildings, balance))
+        .unwrap_or_default();
 
     // Here is where the battle happens
-    let BattleResult {
-        winner,
-        rounds,
-        att_losses,
-        def_losses,
-    } = fleet_battle(fleet, fleet_target);
+    let battle_result = fleet_battle(fleet, fleet_target, &mut planet, balance);
+
+    // Destroyed squadrons are gone for good, survivors of the losing defender fall back and
+    // the winning attacker may take the freed position
+    fleet.settle_after_battle(slot);
+    fleet_target.settle_after_battle(slot);
+    let fuel = fleet.get_move_quote((x, y), (target_x, target_y));
+    let mut retreat = None;
+    let mut advanced = false;
+    let mut bombarded = None;
+    let mut plunder = Resources::default();
+    if matches!(battle_result.winner, BattleSide::Attacker) {
+        // With the planet's defenses down, surviving explosives can be aimed at a building
+        // and hauling bays load up the production the defender hasn't harvested yet
+        if let Some(holding) = holding.as_mut() {
+            if let Some(building_type) = bombard {
+                let explosive = fleet.get_battle_strength(balance).weapons.explosive;
+                let levels_lost = holding.bombard(building_type, explosive, balance)?;
+                bombarded = Some((building_type, levels_lost));
+            }
+            plunder = holding.plunder(
+                get_planet_resources(target_x, target_y),
+                slot,
+                fleet.cargo_capacity(balance),
+                balance,
+            );
+            holding.save_to(&ctx.accounts.planet_holding.to_account_info())?;
+        } else if bombard.is_some() {
+            return Err(BuildingErrorCode::BuildingNotPresent.into());
+        }
+        // A blocked retreat falls back to the other positions around the target, passed in
+        // the remaining accounts
+        retreat = if fleet_target.retreat_into(&mut ctx.accounts.fleet_retreat, slot) {
+            Some((retreat_x, retreat_y))
+        } else {
+            retreat_into_fallback(
+                fleet_target,
+                ((x, y), (target_x, target_y), (retreat_x, retreat_y)),
+                ctx.remaining_accounts,
+                slot,
+            )?
+        };
+        if advance && fleet.is_present() && fleet_target.is_free(slot) {
+            fleet_target.replace_with_another_fleet(fleet);
+            fleet.reset();
+            advanced = true;
+        }
+    }
+
+    // Part of the destroyed ships is left behind for salvagers
+    let debris = ctx
+        .accounts
+        .debris_field
+        .add_losses(&battle_result, balance);
 
-    // Calculate resources the winner gets
-    let resource_gain = match winner {
-        BattleSide::Attacker => att_losses.div(3).sum(def_losses.div(5)),
-        BattleSide::Defender => def_losses.div(3).sum(att_losses.div(5)),
-    };
+    // Keep the report of the battle
+    let battle_id = ctx.accounts.player_info.open_battle_report();
+    ctx.accounts.battle_report.record(
+        battle_id,
+        (ctx.accounts.signer.key(), defender),
+        ((x, y), (target_x, target_y)),
+        &battle_result,
+    )?;
 
-    // Determine winner account
-    let winner_player_cache = match winner {
-        BattleSide::Attacker => &mut ctx.accounts.player_cache,
-        BattleSide::Defender => &mut ctx.accounts.player_cache_target,
-    };
-    // Add resources to account
-    winner_player_cache.resources = winner_player_cache.resources.sum(resource_gain);
+    // Pay out the winner and count the battle for both players
+    let rewards = BattleRewards::from_result(&battle_result);
+    rewards.credit(
+        &mut ctx.accounts.player_cache,
+        &mut ctx.accounts.player_cache_target,
+    );
+    let attacker_won = matches!(battle_result.winner, BattleSide::Attacker);
+    ctx.accounts.player_cache.resources = ctx.accounts.player_cache.resources.sum(plunder.clone());
+    ctx.accounts.player_info.record_battle(attacker_won);
+    ctx.accounts.defender_info.record_battle(!attacker_won);
+
+    emit!(FleetAttacked {
+        battle_id,
+        attacker: ctx.accounts.signer.key(),
+        defender,
+        x,
+        y,
+        target_x,
+        target_y,
+        winner: battle_result.winner,
+        att_losses: battle_result.att_losses.clone(),
+        def_losses: battle_result.def_losses.clone(),
+        reward: rewards.winner_reward(battle_result.winner),
+        retreat_x: retreat.map_or(retreat_x, |(x, _)| x),
+        retreat_y: retreat.map_or(retreat_y, |(_, y)| y),
+        retreated: retreat.is_some(),
+        advanced,
+        plunder,
+        debris,
+    });
+    if let Some((building_type, levels_lost)) = bombarded {
+        emit!(PlanetBombarded {
+            battle_id,
+            attacker: ctx.accounts.signer.key(),
+            defender,
+            x: target_x,
+            y: target_y,
+            building_type,
+            levels_lost,
+        });
+    }
 
     // Burn fuel of the attacker
     process_burn_resource(
@@ -50,12 +170,12 @@ pub fn fleet_attack(
                 ctx.bumps.resource_authority,
             ),
         ),
-        fleet.get_move_quote((x, y), (target_x, target_y)),
+        fuel,
     )
 }
 
 #[derive(Accounts)]
-#[instruction(x: u16, y: u16, target_x: u16, target_y: u16)]
+#[instruction(x: u16, y: u16, target_x: u16, target_y: u16, retreat_x: u16, retreat_y: u16)]
 pub struct FleetAttack<'info> {
     #[account(mut)]
     pub signer: Signer<'info>,
@@ -70,23 +190,94 @@ pub struct FleetAttack<'info> {
         bump,
         constraint = fleet.is_present() @ FleetErrorCode::FleetNotPresent,
         constraint = fleet.is_owned_by(&signer.key()) @ FleetErrorCode::NoAuthority,
+        constraint = !fleet.is_in_transit() @ FleetErrorCode::FleetInTransit,
     )]
     pub fleet: Account<'info, Fleet>,
-    // To
+    // To, a planet without a fleet may have never had a fleet account
     #[account(
-        mut,
+        init_if_needed,
+        payer = signer,
+        space = 8 + Fleet::INIT_SPACE,
+        seeds = [
+            seeds::FLEET,
+            target_x.to_le_bytes().as_ref(),
+            target_y.to_le_bytes().as_ref()
+        ],
+        bump,
+        constraint = !fleet_target.is_owned_by(&signer.key()) @ FleetErrorCode::NoAuthority,
+        constraint = !fleet_target.is_in_transit() @ FleetErrorCode::FleetInTransit,
+    )]
+    pub fleet_target: Box<Account<'info, Fleet>>,
+    /// CHECK: Player defending the target, owner of the fleet there or the owner of the planet
+    /// when no fleet is there, which `planet_holding` has to exist for
+    #[account(
+        constraint = !fleet_target.is_present() || defender.key() == fleet_target.owner @ FleetErrorCode::NoAuthority,
+        constraint = defender.key() != signer.key() @ FleetErrorCode::NoAuthority,
+    )]
+    pub defender: UncheckedAccount<'info>,
+    // Where the defender falls back to if it loses
+    #[account(
+        init_if_needed,
+        payer = signer,
+        space = 8 + Fleet::INIT_SPACE,
         seeds = [
             seeds::FLEET,
+            retreat_x.to_le_bytes().as_ref(),
+            retreat_y.to_le_bytes().as_ref()
+        ],
+        bump,
+        constraint = is_adjacent((target_x, target_y), (retreat_x, retreat_y)) @ FleetErrorCode::InvalidRetreat,
+        constraint = (retreat_x, retreat_y) != (x, y) @ FleetErrorCode::InvalidRetreat,
+    )]
+    pub fleet_retreat: Box<Account<'info, Fleet>>,
+    /// CHECK: Defender's holding at the target, only exists if the defender owns a planet there
+    #[account(
+        mut,
+        seeds = [
+            seeds::PLANET_HOLDING,
+            defender.key().as_ref(),
+            target_x.to_le_bytes().as_ref(),
+            target_y.to_le_bytes().as_ref(),
+        ],
+        bump,
+    )]
+    pub planet_holding: UncheckedAccount<'info>,
+    // Wreckage of the battle
+    #[account(
+        init_if_needed,
+        payer = signer,
+        space = 8 + DebrisField::INIT_SPACE,
+        seeds = [
+            seeds::DEBRIS_FIELD,
             target_x.to_le_bytes().as_ref(),
             target_y.to_le_bytes().as_ref()
         ],
         bump,
-        constraint = fleet_target.is_present() @ FleetErrorCode::FleetNotPresent,
     )]
-    pub fleet_target: Account<'info, Fleet>,
+    pub debris_field: Box<Account<'info, DebrisField>>,
+    // Players on both sides, for their battle stats
+    #[account(mut, seeds = [seeds::PLAYER, signer.key().as_ref()], bump)]
+    pub player_info: Box<Account<'info, Player>>,
+    #[account(mut, seeds = [seeds::PLAYER, defender.key().as_ref()], bump)]
+    pub defender_info: Box<Account<'info, Player>>,
+    // Report of the battle, numbered per attacker
+    #[account(
+        init,
+        seeds = [
+            seeds::BATTLE_REPORT,
+            signer.key().as_ref(),
+            player_info.battle_reports.to_le_bytes().as_ref()
+        ],
+        bump,
+        space = 8 + BattleReport::INIT_SPACE,
+        payer = signer,
+    )]
+    pub battle_report: Box<Account<'info, BattleReport>>,
     // Resource authority
     #[account(mut, seeds = [seeds::RESOURCE_AUTHORITY], bump)]
     pub resource_authority: Account<'info, ResourceAuthority>,
+    #[account(seeds = [seeds::BALANCE_CONFIG], bump)]
+    pub balance_config: Box<Account<'info, BalanceConfig>>,
 
     // Player Resource caches - we use this to minimize transaction size
     #[account(
@@ -102,12 +293,11 @@ pub struct FleetAttack<'info> {
         mut,
         seeds = [
            seeds::PLAYER_CACHE,
-           signer.key().as_ref()
+           defender.key().as_ref()
         ],
         bump,
     )]
     pub player_cache_target: Account<'info, PlayerCache>,
-
     // Fuel
     #[account(mut, seeds = [seeds::MINT_FUEL], bump)]
     pub mint_fuel: Account<'info, Mint>,
@@ -0,0 +1,99 @@
+use crate::{
+    balance::BalanceConfig,
+    building::BuildingType,
+    event::FleetDisbanded,
+    fleet::{Fleet, FleetErrorCode},
+    planet::{PlanetErrorCode, PlanetHolding, PlanetInfo},
+    resource::PlayerCache,
+    seeds,
+};
+use anchor_lang::prelude::*;
+
+pub fn fleet_disband(ctx: Context<FleetDisband>, x: u16, y: u16) -> Result<()> {
+    if ctx
+        .accounts
+        .planet_holding
+        .building_level(BuildingType::Shipyard)
+        == 0
+    {
+        return Err(FleetErrorCode::NoShipyardOnPlanet.into());
+    }
+
+    // Part of the fleet's value is salvaged into the player cache
+    let balance = &ctx.accounts.balance_config.values;
+    let refund = ctx
+        .accounts
+        .fleet
+        .get_quote(balance)
+        .mul(balance.disband_refund_percent as u64)
+        .div(100)
+        .to_whole_units();
+    let player_cache = &mut ctx.accounts.player_cache;
+    player_cache.resources = player_cache.resources.sum(refund.clone());
+    // Coordinate is empty now, the account's rent is returned to the owner unless the
+    // position is still reserved for another fleet travelling here
+    ctx.accounts.fleet.reset();
+    if ctx.accounts.fleet.is_free(Clock::get()?.slot) {
+        ctx.accounts
+            .fleet
+            .close(ctx.accounts.signer.to_account_info())?;
+    }
+
+    emit!(FleetDisbanded {
+        owner: ctx.accounts.signer.key(),
+        x,
+        y,
+        refund,
+    });
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(x: u16, y: u16)]
+pub struct FleetDisband<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [
+            seeds::FLEET,
+            x.to_le_bytes().as_ref(),
+            y.to_le_bytes().as_ref(),
+        ],
+        bump,
+        constraint = fleet.is_present() @ FleetErrorCode::FleetNotPresent,
+        constraint = fleet.is_owned_by(&signer.key()) @ FleetErrorCode::NoAuthority,
+        constraint = !fleet.is_in_transit() @ FleetErrorCode::FleetInTransit,
+    )]
+    pub fleet: Account<'info, Fleet>,
+    // Fleet has to be parked at an owned planet
+    #[account(
+        seeds = [
+            seeds::PLANET_INFO,
+            x.to_le_bytes().as_ref(),
+            y.to_le_bytes().as_ref()
+        ],
+        bump,
+        constraint = planet_info.is_owned_by(&signer.key()) @ PlanetErrorCode::NoAuthority,
+    )]
+    pub planet_info: Account<'info, PlanetInfo>,
+    #[account(
+        seeds = [
+            seeds::PLANET_HOLDING,
+            signer.key().as_ref(),
+            x.to_le_bytes().as_ref(),
+            y.to_le_bytes().as_ref(),
+        ],
+        bump,
+    )]
+    pub planet_holding: Account<'info, PlanetHolding>,
+    #[account(
+        mut,
+        seeds = [seeds::PLAYER_CACHE, signer.key().as_ref()],
+        bump,
+    )]
+    pub player_cache: Account<'info, PlayerCache>,
+    #[account(seeds = [seeds::BALANCE_CONFIG], bump)]
+    pub balance_config: Box<Account<'info, BalanceConfig>>,
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,90 @@
+use crate::{
+    event::FleetMerged,
+    fleet::{is_adjacent, Fleet, FleetErrorCode},
+    resource::{process_burn_resource, ResourceAuthority},
+    seeds,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, Token, TokenAccount};
+
+pub fn fleet_merge(
+    ctx: Context<FleetMerge>,
+    x: u16,
+    y: u16,
+    from_x: u16,
+    from_y: u16,
+) -> Result<()> {
+    if !is_adjacent((x, y), (from_x, from_y)) {
+        return Err(FleetErrorCode::NotAdjacent.into());
+    }
+    let fleet = &mut ctx.accounts.fleet;
+    let fleet_from = &mut ctx.accounts.fleet_from;
+    let fuel = fleet_from.get_move_quote((from_x, from_y), (x, y));
+    fleet.absorb(fleet_from.squadrons())?;
+    fleet.last_salvage_slot = fleet.last_salvage_slot.max(fleet_from.last_salvage_slot);
+    fleet_from.reset();
+    emit!(FleetMerged {
+        owner: ctx.accounts.signer.key(),
+        x,
+        y,
+        from_x,
+        from_y,
+        fuel,
+    });
+    process_burn_resource(
+        &ctx.accounts.token_program,
+        (
+            &ctx.accounts.account_fuel,
+            &ctx.accounts.mint_fuel,
+            (
+                &ctx.accounts.resource_authority,
+                ctx.bumps.resource_authority,
+            ),
+        ),
+        fuel,
+    )
+}
+
+#[derive(Accounts)]
+#[instruction(x: u16, y: u16, from_x: u16, from_y: u16)]
+pub struct FleetMerge<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    // Fleet that stays
+    #[account(
+        mut,
+        seeds = [
+            seeds::FLEET,
+            x.to_le_bytes().as_ref(),
+            y.to_le_bytes().as_ref(),
+        ],
+        bump,
+        constraint = fleet.is_present() @ FleetErrorCode::FleetNotPresent,
+        constraint = fleet.is_owned_by(&signer.key()) @ FleetErrorCode::NoAuthority,
+        constraint = !fleet.is_in_transit() @ FleetErrorCode::FleetInTransit,
+    )]
+    pub fleet: Account<'info, Fleet>,
+    // Fleet that is folded in
+    #[account(
+        mut,
+        seeds = [
+            seeds::FLEET,
+            from_x.to_le_bytes().as_ref(),
+            from_y.to_le_bytes().as_ref(),
+        ],
+        bump,
+        constraint = fleet_from.is_present() @ FleetErrorCode::FleetNotPresent,
+        constraint = fleet_from.is_owned_by(&signer.key()) @ FleetErrorCode::NoAuthority,
+        constraint = !fleet_from.is_in_transit() @ FleetErrorCode::FleetInTransit,
+    )]
+    pub fleet_from: Account<'info, Fleet>,
+    // Resource authority
+    #[account(mut, seeds = [seeds::RESOURCE_AUTHORITY], bump)]
+    pub resource_authority: Account<'info, ResourceAuthority>,
+    #[account(mut, seeds = [seeds::MINT_FUEL], bump)]
+    pub mint_fuel: Account<'info, Mint>,
+    #[account(mut, seeds = [seeds::ACCOUNT_FUEL, signer.key().as_ref()], bump)]
+    pub account_fuel: Account<'info, TokenAccount>,
+    // Programs
+    pub token_program: Program<'info, Token>,
+}
@@ -1,4 +1,6 @@
 use crate::{
+    balance::BalanceConfig,
+    event::FleetMoved,
     fleet::{Fleet, FleetErrorCode},
     resource::{process_burn_resource, ResourceAuthority},
     seeds,
@@ -13,10 +15,35 @@ pub fn fleet_move(
     move_to_x: u16,
     move_to_y: u16,
 ) -> Result<()> {
-    let fleet_to = &mut ctx.accounts.fleet_to;
+    let slot = Clock::get()?.slot;
+    if !ctx.accounts.fleet_to.is_free(slot) {
+        return Err(FleetErrorCode::IllegalMoveAlreadyOccupied.into());
+    }
+
+    // Fleet stays where it is until it arrives with `fleet_arrive`, its destination is kept
+    // free for it until then
     let fleet_from = &mut ctx.accounts.fleet_from;
-    fleet_to.replace_with_another_fleet(fleet_from);
-    fleet_from.reset();
+    let travel_slots = fleet_from.get_travel_slots(
+        (x, y),
+        (move_to_x, move_to_y),
+        &ctx.accounts.balance_config.values,
+    );
+    fleet_from.depart((move_to_x, move_to_y), slot, travel_slots);
+    let arrival_slot = fleet_from
+        .transit
+        .map(|transit| transit.arrival_slot)
+        .unwrap_or_default();
+    ctx.accounts.fleet_to.reserve(arrival_slot);
+    let fuel = fleet_from.get_move_quote((x, y), (move_to_x, move_to_y));
+    emit!(FleetMoved {
+        owner: ctx.accounts.signer.key(),
+        x,
+        y,
+        move_to_x,
+        move_to_y,
+        fuel,
+        arrival_slot,
+    });
     process_burn_resource(
         &ctx.accounts.token_program,
         (
@@ -27,7 +54,7 @@ pub fn fleet_move(
                 ctx.bumps.resource_authority,
             ),
         ),
-        ctx.accounts.fleet_to.get_move_quote((x, y), (move_to_x, move_to_y)),
+        fuel,
     )
 }
 
@@ -47,6 +74,7 @@ pub struct FleetMove<'info> {
         bump,
         constraint = fleet_from.is_present() @ FleetErrorCode::FleetNotPresent,
         constraint = fleet_from.is_owned_by(&signer.key()) @ FleetErrorCode::NoAuthority,
+        constraint = !fleet_from.is_in_transit() @ FleetErrorCode::FleetInTransit,
     )]
     pub fleet_from: Account<'info, Fleet>,
     // To
@@ -60,12 +88,13 @@ pub struct FleetMove<'info> {
         bump,
         space = 8 + Fleet::INIT_SPACE,
         payer = signer,
-        constraint = !fleet_to.is_present() @ FleetErrorCode::IllegalMoveAlreadyOccupied,
     )]
     pub fleet_to: Account<'info, Fleet>,
     // Resource authority
     #[account(mut, seeds = [seeds::RESOURCE_AUTHORITY], bump)]
     pub resource_authority: Account<'info, ResourceAuthority>,
+    #[account(seeds = [seeds::BALANCE_CONFIG], bump)]
+    pub balance_config: Box<Account<'info, BalanceConfig>>,
     #[account(mut, seeds = [seeds::MINT_FUEL], bump)]
     pub mint_fuel: Account<'info, Mint>,
     #[account(mut, seeds = [seeds::ACCOUNT_FUEL, signer.key().as_ref()], bump)]
@@ -1,47 +1,88 @@
 use anchor_lang::prelude::*;
 use anchor_spl::token::{ Mint, Token, TokenAccount };
-use crate::{building::BuildingType, fleet::{Fleet, FleetErrorCode, Squadron, SquadronBlueprint, SQUADRONS_IN_FLEET}, planet::*, process_burn_igt, resource::{burn_resources, ResourceAuthority, Resources}, seeds };
+use crate::{balance::BalanceConfig, building::BuildingType, event::FleetCreated, fleet::{DesignedSquadron, Fleet, FleetErrorCode, ShipDesign, SquadronBlueprint, SQUADRONS_IN_FLEET}, planet::*, process_burn_igt, resource::{burn_resources, ResourceAuthority, Resources}, seeds };
 
-pub fn fleet_new(ctx: Context<FleetNew>, template: [Option<SquadronBlueprint>; SQUADRONS_IN_FLEET]) -> Result<()> {
+pub fn fleet_new(ctx: Context<FleetNew>, x: u16, y: u16, template: [Option<SquadronBlueprint>; SQUADRONS_IN_FLEET]) -> Result<()> {
     let shipyard = ctx.accounts.planet_holding.buildings.iter_mut().find(|b| b.building_type == BuildingType::Shipyard);
     if shipyard.is_none() {
         return Err(FleetErrorCode::NoShipyardOnPlanet.into())
     }; 
-    let fleet = &mut ctx.accounts.fleet;
-    fleet.set_presence(ctx.accounts.signer.key());
-    fleet.can_be_built(ctx.accounts.planet_holding.buildings)?;
-    fleet.build_from_template(template);
-    let quote = Resources::default(); // fleet.get_quote();
-    Ok(())
-    // burn_resources(
-    //     quote.clone(), 
-    //     &ctx.accounts.token_program, 
-    //     &ctx.accounts.resource_authority, 
-    //     ctx.bumps.resource_authority,
-    //     (
-    //         &ctx.accounts.mint_metal, 
-    //         &ctx.accounts.mint_crystal, 
-    //         &ctx.accounts.mint_chemical, 
-    //         &ctx.accounts.mint_fuel
-    //     ),
-    //     (
-    //         &ctx.accounts.account_metal, 
-    //         &ctx.accounts.account_crystal, 
-    //         &ctx.accounts.account_chemical, 
-    //         &ctx.accounts.account_fuel
-    //     )
-    // )
-    // process_burn_igt(&ctx.accounts.token_program, (
-    //     // from
-    //     &ctx.accounts.account_igt,
-    //     // mint
-    //     &ctx.accounts.mint_igt,
-    //     // authority
-    //     &ctx.accounts.mint_igt
-    // ), quote.igt)
+    let mut quote = Resources::default();
+    for blueprint in template.iter().flatten() {
+        blueprint.can_be_built(ctx.accounts.planet_holding.buildings)?;
+        quote = quote.sum(blueprint.get_quote(&ctx.accounts.balance_config.values));
+    }
+
+    // NEW SHIPS JOIN THE FLEET ALREADY PARKED AT THE SHIPYARD
+    ctx.accounts.fleet.reinforce(ctx.accounts.signer.key(), template)?;
+
+    // CHECK IF PLAYER CAN AFFORD THE FLEET
+    let available = Resources {
+        igt: ctx.accounts.account_igt.amount,
+        metal: ctx.accounts.account_metal.amount,
+        crystal: ctx.accounts.account_crystal.amount,
+        chemical: ctx.accounts.account_chemical.amount,
+        fuel: ctx.accounts.account_fuel.amount,
+    };
+    if !available.covers(&quote) {
+        return Err(FleetErrorCode::NotEnoughResources.into())
+    }
+
+    emit!(FleetCreated {
+        owner: ctx.accounts.signer.key(),
+        x,
+        y,
+        cost: quote.clone(),
+    });
+
+    // PAY FOR THE FLEET
+    burn_resources(
+        quote.clone(), 
+        &ctx.accounts.token_program, 
+        &ctx.accounts.resource_authority, 
+        ctx.bumps.resource_authority,
+        (
+            &ctx.accounts.mint_metal, 
+            &ctx.accounts.mint_crystal, 
+            &ctx.accounts.mint_chemical, 
+            &ctx.accounts.mint_fuel
+        ),
+        (
+            &ctx.accounts.account_metal, 
+            &ctx.accounts.account_crystal, 
+            &ctx.accounts.account_chemical, 
+            &ctx.accounts.account_fuel
+        )
+    )?;
+    process_burn_igt(&ctx.accounts.token_program, (
+        &ctx.accounts.account_igt,
+        &ctx.accounts.mint_igt,
+        &ctx.accounts.signer
+    ), quote.igt)
+}
+/// Builds a fleet from saved designs, `ShipDesign` accounts are passed as remaining accounts in
+/// the order of the squadrons that use them
+pub fn fleet_new_from_designs(ctx: Context<FleetNew>, x: u16, y: u16, squadrons: [Option<DesignedSquadron>; SQUADRONS_IN_FLEET]) -> Result<()> {
+    let mut template: [Option<SquadronBlueprint>; SQUADRONS_IN_FLEET] = [None; SQUADRONS_IN_FLEET];
+    let mut design_accounts = ctx.remaining_accounts.iter();
+    for (index, squadron) in squadrons.iter().enumerate() {
+        if let Some(squadron) = squadron {
+            let account = design_accounts.next().ok_or(FleetErrorCode::InvalidShipDesign)?;
+            if account.owner != &crate::ID {
+                return Err(FleetErrorCode::InvalidShipDesign.into())
+            }
+            let design = ShipDesign::try_deserialize(&mut &account.data.borrow()[..])?;
+            if design.owner != ctx.accounts.signer.key() || design.index != squadron.design {
+                return Err(FleetErrorCode::InvalidShipDesign.into())
+            }
+            template[index] = Some(SquadronBlueprint::from_design(&design, squadron.amount));
+        }
+    }
+    fleet_new(ctx, x, y, template)
 }
+
 #[derive(Accounts)]
-#[instruction(x: u16, y: u16, template: [Option<SquadronBlueprint>; SQUADRONS_IN_FLEET])]
+#[instruction(x: u16, y: u16)]
 pub struct FleetNew<'info> {
     #[account(mut)]
     pub signer: Signer<'info>,
@@ -70,41 +111,42 @@ pub struct FleetNew<'info> {
         space = 8 + Fleet::INIT_SPACE,
         payer = signer
     )]
-    pub fleet: Account<'info, Fleet>,
+    pub fleet: Box<Account<'info, Fleet>>,
 
     // Resource authority
     #[account(mut, seeds = [seeds::RESOURCE_AUTHORITY], bump)]
     pub resource_authority: Account<'info, ResourceAuthority>,
+    #[account(seeds = [seeds::BALANCE_CONFIG], bump)]
+    pub balance_config: Box<Account<'info, BalanceConfig>>,
 
     // Mints
-    // #[account(mut, seeds = [seeds::MINT_IGT], bump)]
-    // pub mint_igt: Account<'info, Mint>,
-    // #[account(mut, seeds = [seeds::MINT_METAL], bump)]
-    // pub mint_metal: Account<'info, Mint>,
-    // #[account(mut, seeds = [seeds::MINT_CHEMICAL], bump)]
-    // pub mint_chemical: Account<'info, Mint>,
-    // #[account(mut, seeds = [seeds::MINT_CRYSTAL], bump)]
-    // pub mint_crystal: Account<'info, Mint>,
-    // #[account(mut, seeds = [seeds::MINT_FUEL], bump)]
-    // pub mint_fuel: Account<'info, Mint>,
+    #[account(mut, seeds = [seeds::MINT_IGT], bump)]
+    pub mint_igt: Box<Account<'info, Mint>>,
+    #[account(mut, seeds = [seeds::MINT_METAL], bump)]
+    pub mint_metal: Box<Account<'info, Mint>>,
+    #[account(mut, seeds = [seeds::MINT_CHEMICAL], bump)]
+    pub mint_chemical: Box<Account<'info, Mint>>,
+    #[account(mut, seeds = [seeds::MINT_CRYSTAL], bump)]
+    pub mint_crystal: Box<Account<'info, Mint>>,
+    #[account(mut, seeds = [seeds::MINT_FUEL], bump)]
+    pub mint_fuel: Box<Account<'info, Mint>>,
 
     // User resource token accounts
-    // #[account(
-    //     mut,
-    //     associated_token::mint = mint_igt,
-    //     associated_token::authority = signer 
-    // )]
-    // pub account_igt: Account<'info, TokenAccount>,
-    // #[account(mut, seeds = [seeds::ACCOUNT_METAL, signer.key().as_ref()], bump)]
-    // pub account_metal: Account<'info, TokenAccount>,
-    // #[account(mut, seeds = [seeds::ACCOUNT_CRYSTAL, signer.key().as_ref()], bump)]
-    // pub account_crystal: Account<'info, TokenAccount>,
-    // #[account(mut, seeds = [seeds::ACCOUNT_CHEMICAL, signer.key().as_ref()], bump)]
-    // pub account_chemical: Account<'info, TokenAccount>,
-    // #[account(mut, seeds = [seeds::ACCOUNT_FUEL, signer.key().as_ref()], bump)]
-    // pub account_fuel: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        associated_token::mint = mint_igt,
+        associated_token::authority = signer 
+    )]
+    pub account_igt: Box<Account<'info, TokenAccount>>,
+    #[account(mut, seeds = [seeds::ACCOUNT_METAL, signer.key().as_ref()], bump)]
+    pub account_metal: Box<Account<'info, TokenAccount>>,
+    #[account(mut, seeds = [seeds::ACCOUNT_CRYSTAL, signer.key().as_ref()], bump)]
+    pub account_crystal: Box<Account<'info, TokenAccount>>,
+    #[account(mut, seeds = [seeds::ACCOUNT_CHEMICAL, signer.key().as_ref()], bump)]
+    pub account_chemical: Box<Account<'info, TokenAccount>>,
+    #[account(mut, seeds = [seeds::ACCOUNT_FUEL, signer.key().as_ref()], bump)]
+    pub account_fuel: Box<Account<'info, TokenAccount>>,
 
     pub token_program: Program<'info, Token>,
     pub system_program: Program<'info, System>,
 }
-
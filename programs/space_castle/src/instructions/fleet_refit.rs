@@ -0,0 +1,140 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{ Mint, Token, TokenAccount };
+use crate::{balance::BalanceConfig, building::BuildingType, event::FleetRefitted, fleet::{ship_can_be_built, ship_quote, Fleet, FleetErrorCode, ShipTemplate}, planet::*, process_burn_igt, resource::{burn_resources, ResourceAuthority, Resources}, seeds };
+
+pub fn fleet_refit(ctx: Context<FleetRefit>, x: u16, y: u16, squadron: u8, template: ShipTemplate) -> Result<()> {
+    if ctx.accounts.planet_holding.building_level(BuildingType::Shipyard) == 0 {
+        return Err(FleetErrorCode::NoShipyardOnPlanet.into())
+    }
+    ship_can_be_built(template, ctx.accounts.planet_holding.buildings)?;
+
+    // ONLY THE DIFFERENCE IN VALUE IS PAID, DOWNGRADES ARE FREE
+    let balance = &ctx.accounts.balance_config.values;
+    let (previous, amount) = ctx.accounts.fleet.refit(squadron as usize, template)?;
+    let cost = ship_quote(&template, amount, balance).sub(ship_quote(&previous, amount, balance));
+
+    // CHECK IF PLAYER CAN AFFORD THE REFIT
+    let available = Resources {
+        igt: ctx.accounts.account_igt.amount,
+        metal: ctx.accounts.account_metal.amount,
+        crystal: ctx.accounts.account_crystal.amount,
+        chemical: ctx.accounts.account_chemical.amount,
+        fuel: ctx.accounts.account_fuel.amount,
+    };
+    if !available.covers(&cost) {
+        return Err(FleetErrorCode::NotEnoughResources.into())
+    }
+
+    emit!(FleetRefitted {
+        owner: ctx.accounts.signer.key(),
+        x,
+        y,
+        squadron,
+        cost: cost.clone(),
+    });
+
+    // PAY FOR THE REFIT
+    burn_resources(
+        cost.clone(), 
+        &ctx.accounts.token_program, 
+        &ctx.accounts.resource_authority, 
+        ctx.bumps.resource_authority,
+        (
+            &ctx.accounts.mint_metal, 
+            &ctx.accounts.mint_crystal, 
+            &ctx.accounts.mint_chemical, 
+            &ctx.accounts.mint_fuel
+        ),
+        (
+            &ctx.accounts.account_metal, 
+            &ctx.accounts.account_crystal, 
+            &ctx.accounts.account_chemical, 
+            &ctx.accounts.account_fuel
+        )
+    )?;
+    process_burn_igt(&ctx.accounts.token_program, (
+        &ctx.accounts.account_igt,
+        &ctx.accounts.mint_igt,
+        &ctx.accounts.signer
+    ), cost.igt)
+}
+
+#[derive(Accounts)]
+#[instruction(x: u16, y: u16)]
+pub struct FleetRefit<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    // Planet
+    #[account(
+        seeds = [
+            seeds::PLANET_INFO, 
+            x.to_le_bytes().as_ref(), 
+            y.to_le_bytes().as_ref()
+        ],
+        bump, 
+        constraint = planet_info.is_owned_by(&signer.key()) @ PlanetErrorCode::NoAuthority,
+    )]
+    pub planet_info: Box<Account<'info, PlanetInfo>>,
+    #[account(
+        seeds = [
+            seeds::PLANET_HOLDING,
+            signer.key().as_ref(),
+            x.to_le_bytes().as_ref(), 
+            y.to_le_bytes().as_ref(), 
+        ], 
+        bump, 
+    )]
+    pub planet_holding: Box<Account<'info, PlanetHolding>>,
+
+    // Fleet
+    #[account(
+        mut,
+        seeds = [
+            seeds::FLEET,
+            x.to_le_bytes().as_ref(),
+            y.to_le_bytes().as_ref()
+        ],
+        bump,
+        constraint = fleet.is_present() @ FleetErrorCode::FleetNotPresent,
+        constraint = fleet.is_owned_by(&signer.key()) @ FleetErrorCode::NoAuthority,
+        constraint = !fleet.is_in_transit() @ FleetErrorCode::FleetInTransit,
+    )]
+    pub fleet: Box<Account<'info, Fleet>>,
+
+    // Resource authority
+    #[account(mut, seeds = [seeds::RESOURCE_AUTHORITY], bump)]
+    pub resource_authority: Account<'info, ResourceAuthority>,
+    #[account(seeds = [seeds::BALANCE_CONFIG], bump)]
+    pub balance_config: Box<Account<'info, BalanceConfig>>,
+
+    // Mints
+    #[account(mut, seeds = [seeds::MINT_IGT], bump)]
+    pub mint_igt: Box<Account<'info, Mint>>,
+    #[account(mut, seeds = [seeds::MINT_METAL], bump)]
+    pub mint_metal: Box<Account<'info, Mint>>,
+    #[account(mut, seeds = [seeds::MINT_CHEMICAL], bump)]
+    pub mint_chemical: Box<Account<'info, Mint>>,
+    #[account(mut, seeds = [seeds::MINT_CRYSTAL], bump)]
+    pub mint_crystal: Box<Account<'info, Mint>>,
+    #[account(mut, seeds = [seeds::MINT_FUEL], bump)]
+    pub mint_fuel: Box<Account<'info, Mint>>,
+
+    // User resource token accounts
+    #[account(
+        mut,
+        associated_token::mint = mint_igt,
+        associated_token::authority = signer 
+    )]
+    pub account_igt: Box<Account<'info, TokenAccount>>,
+    #[account(mut, seeds = [seeds::ACCOUNT_METAL, signer.key().as_ref()], bump)]
+    pub account_metal: Box<Account<'info, TokenAccount>>,
+    #[account(mut, seeds = [seeds::ACCOUNT_CRYSTAL, signer.key().as_ref()], bump)]
+    pub account_crystal: Box<Account<'info, TokenAccount>>,
+    #[account(mut, seeds = [seeds::ACCOUNT_CHEMICAL, signer.key().as_ref()], bump)]
+    pub account_chemical: Box<Account<'info, TokenAccount>>,
+    #[account(mut, seeds = [seeds::ACCOUNT_FUEL, signer.key().as_ref()], bump)]
+    pub account_fuel: Box<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+}
@@ -0,0 +1,92 @@
+use crate::{
+    balance::BalanceConfig,
+    battle::DebrisField,
+    event::FleetSalvaged,
+    fleet::{is_adjacent, Fleet, FleetErrorCode},
+    resource::PlayerCache,
+    seeds,
+};
+use anchor_lang::prelude::*;
+
+pub fn fleet_salvage(
+    ctx: Context<FleetSalvage>,
+    x: u16,
+    y: u16,
+    debris_x: u16,
+    debris_y: u16,
+) -> Result<()> {
+    if (x, y) != (debris_x, debris_y) && !is_adjacent((x, y), (debris_x, debris_y)) {
+        return Err(FleetErrorCode::NotAdjacent.into());
+    }
+    let balance = &ctx.accounts.balance_config.values;
+    let slot = Clock::get()?.slot;
+    let fleet = &mut ctx.accounts.fleet;
+    let cargo_capacity = fleet.cargo_capacity(balance);
+    if cargo_capacity == 0 {
+        return Err(FleetErrorCode::NoCargoCapacity.into());
+    }
+    // Hauling bays carry one load at a time
+    if !fleet.can_salvage(slot, balance.salvage_cooldown_slots) {
+        return Err(FleetErrorCode::SalvageOnCooldown.into());
+    }
+    let salvaged = ctx.accounts.debris_field.salvage(cargo_capacity);
+    if salvaged.is_empty() {
+        return Err(FleetErrorCode::NothingToSalvage.into());
+    }
+    fleet.last_salvage_slot = slot;
+    let player_cache = &mut ctx.accounts.player_cache;
+    player_cache.resources = player_cache.resources.sum(salvaged.clone());
+    emit!(FleetSalvaged {
+        owner: ctx.accounts.signer.key(),
+        x,
+        y,
+        debris_x,
+        debris_y,
+        resources: salvaged,
+    });
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(x: u16, y: u16, debris_x: u16, debris_y: u16)]
+pub struct FleetSalvage<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    // Salvaging fleet
+    #[account(
+        mut,
+        seeds = [
+            seeds::FLEET,
+            x.to_le_bytes().as_ref(),
+            y.to_le_bytes().as_ref(),
+        ],
+        bump,
+        constraint = fleet.is_present() @ FleetErrorCode::FleetNotPresent,
+        constraint = fleet.is_owned_by(&signer.key()) @ FleetErrorCode::NoAuthority,
+        constraint = !fleet.is_in_transit() @ FleetErrorCode::FleetInTransit,
+    )]
+    pub fleet: Account<'info, Fleet>,
+    // Debris left by battles
+    #[account(
+        mut,
+        seeds = [
+            seeds::DEBRIS_FIELD,
+            debris_x.to_le_bytes().as_ref(),
+            debris_y.to_le_bytes().as_ref(),
+        ],
+        bump,
+    )]
+    pub debris_field: Account<'info, DebrisField>,
+    #[account(seeds = [seeds::BALANCE_CONFIG], bump)]
+    pub balance_config: Box<Account<'info, BalanceConfig>>,
+    #[account(
+        mut,
+        seeds = [
+           seeds::PLAYER_CACHE,
+           signer.key().as_ref()
+        ],
+        bump,
+    )]
+    pub player_cache: Account<'info, PlayerCache>,
+    pub system_program: Program<'info, System>,
+}
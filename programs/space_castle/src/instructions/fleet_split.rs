@@ -0,0 +1,96 @@
+use crate::{
+    event::FleetSplitOff,
+    fleet::{is_adjacent, Fleet, FleetErrorCode, SQUADRONS_IN_FLEET},
+    resource::{process_burn_resource, ResourceAuthority},
+    seeds,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, Token, TokenAccount};
+
+pub fn fleet_split(
+    ctx: Context<FleetSplit>,
+    x: u16,
+    y: u16,
+    to_x: u16,
+    to_y: u16,
+    amounts: [u16; SQUADRONS_IN_FLEET],
+) -> Result<()> {
+    if !is_adjacent((x, y), (to_x, to_y)) {
+        return Err(FleetErrorCode::NotAdjacent.into());
+    }
+    if !ctx.accounts.fleet_to.is_free(Clock::get()?.slot) {
+        return Err(FleetErrorCode::IllegalMoveAlreadyOccupied.into());
+    }
+    let fleet_from = &mut ctx.accounts.fleet_from;
+    let fleet_to = &mut ctx.accounts.fleet_to;
+    let split = fleet_from.split_off(amounts)?;
+    fleet_to.reset();
+    fleet_to.set_presence(ctx.accounts.signer.key());
+    fleet_to.absorb(split)?;
+    fleet_to.last_salvage_slot = fleet_from.last_salvage_slot;
+    let fuel = fleet_to.get_move_quote((x, y), (to_x, to_y));
+    emit!(FleetSplitOff {
+        owner: ctx.accounts.signer.key(),
+        x,
+        y,
+        to_x,
+        to_y,
+        fuel,
+    });
+    process_burn_resource(
+        &ctx.accounts.token_program,
+        (
+            &ctx.accounts.account_fuel,
+            &ctx.accounts.mint_fuel,
+            (
+                &ctx.accounts.resource_authority,
+                ctx.bumps.resource_authority,
+            ),
+        ),
+        fuel,
+    )
+}
+
+#[derive(Accounts)]
+#[instruction(x: u16, y: u16, to_x: u16, to_y: u16)]
+pub struct FleetSplit<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    // Fleet that is split
+    #[account(
+        mut,
+        seeds = [
+            seeds::FLEET,
+            x.to_le_bytes().as_ref(),
+            y.to_le_bytes().as_ref(),
+        ],
+        bump,
+        constraint = fleet_from.is_present() @ FleetErrorCode::FleetNotPresent,
+        constraint = fleet_from.is_owned_by(&signer.key()) @ FleetErrorCode::NoAuthority,
+        constraint = !fleet_from.is_in_transit() @ FleetErrorCode::FleetInTransit,
+    )]
+    pub fleet_from: Account<'info, Fleet>,
+    // New fleet on an adjacent position
+    #[account(
+        init_if_needed,
+        seeds = [
+            seeds::FLEET,
+            to_x.to_le_bytes().as_ref(),
+            to_y.to_le_bytes().as_ref()
+        ],
+        bump,
+        space = 8 + Fleet::INIT_SPACE,
+        payer = signer,
+    )]
+    pub fleet_to: Account<'info, Fleet>,
+    // Resource authority
+    #[account(mut, seeds = [seeds::RESOURCE_AUTHORITY], bump)]
+    pub resource_authority: Account<'info, ResourceAuthority>,
+    #[account(mut, seeds = [seeds::MINT_FUEL], bump)]
+    pub mint_fuel: Account<'info, Mint>,
+    #[account(mut, seeds = [seeds::ACCOUNT_FUEL, signer.key().as_ref()], bump)]
+    pub account_fuel: Account<'info, TokenAccount>,
+    // Programs
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,36 @@
+use crate::{
+    event::GameAdminChanged,
+    game::{GameConfig, GameErrorCode},
+    program::SpaceCastle,
+    seeds,
+};
+use anchor_lang::prelude::*;
+
+pub fn initialize_game(ctx: Context<InitializeGame>, admin: Pubkey) -> Result<()> {
+    ctx.accounts.game_config.admin = admin;
+    emit!(GameAdminChanged { admin });
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct InitializeGame<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    #[account(
+        init,
+        payer = signer,
+        seeds = [seeds::GAME],
+        bump,
+        space = 8 + GameConfig::INIT_SPACE
+    )]
+    pub game_config: Account<'info, GameConfig>,
+    /// Game can only be initialized by whoever deployed the program
+    #[account(constraint = program.programdata_address()? == Some(program_data.key()))]
+    pub program: Program<'info, SpaceCastle>,
+    #[account(
+        constraint = program_data.upgrade_authority_address == Some(signer.key())
+            @ GameErrorCode::NotUpgradeAuthority
+    )]
+    pub program_data: Account<'info, ProgramData>,
+    pub system_program: Program<'info, System>,
+}
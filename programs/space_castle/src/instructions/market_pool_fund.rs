@@ -1,4 +1,7 @@
-use crate::{market_pool::*, resource::ResourceAuthority, seeds};
+use crate::{
+    event::MarketPoolFunded, market_pool::*, resource::ResourceAuthority, seeds,
+    utilities::convert_to_float,
+};
 use anchor_lang::prelude::*;
 use anchor_spl::{
     associated_token::AssociatedToken,
@@ -28,7 +31,23 @@ pub fn market_pool_fund(
         ),
         pay_in_resource,
         &ctx.accounts.token_program,
-    )
+    )?;
+
+    // Price of the asset at the pool's new ratio, quoted in IGT
+    ctx.accounts.pool_token_account.reload()?;
+    ctx.accounts.pool_igt_token_account.reload()?;
+    let pool_amount = ctx.accounts.pool_token_account.amount;
+    emit!(MarketPoolFunded {
+        payer: ctx.accounts.payer.key(),
+        mint: ctx.accounts.mint.key(),
+        amount,
+        pool_amount,
+        price: convert_to_float(
+            ctx.accounts.pool_igt_token_account.amount,
+            ctx.accounts.igt_mint.decimals,
+        ) / convert_to_float(pool_amount, ctx.accounts.mint.decimals),
+    });
+    Ok(())
 }
 
 #[derive(Accounts)]
@@ -58,6 +77,15 @@ pub struct MarketPoolFund<'info> {
         associated_token::authority = market_pool,
     )]
     pub pool_token_account: Account<'info, TokenAccount>,
+    /// IGT mint, the price of the deposited asset is quoted in it
+    #[account(seeds = [seeds::MINT_IGT], bump)]
+    pub igt_mint: Account<'info, token::Mint>,
+    /// The Liquidity Pool's IGT token account
+    #[account(
+        associated_token::mint = igt_mint,
+        associated_token::authority = market_pool,
+    )]
+    pub pool_igt_token_account: Account<'info, TokenAccount>,
     /// The payer's - or Liquidity Provider's - token account for the asset
     /// being deposited into the pool
     #[account(
@@ -1,4 +1,10 @@
-use crate::{error::MarketPoolError, market_pool::*, seeds};
+use crate::{
+    error::MarketPoolError,
+    event::MarketPoolMinted,
+    game::{GameConfig, GameErrorCode},
+    market_pool::*,
+    seeds,
+};
 use anchor_lang::prelude::*;
 use anchor_spl::{
     associated_token::AssociatedToken,
@@ -23,7 +29,12 @@ pub fn market_pool_mint_to(
                 &ctx.accounts.payer,
                 &ctx.accounts.system_program,
                 &ctx.accounts.token_program,
-            )
+            )?;
+            emit!(MarketPoolMinted {
+                mint: ctx.accounts.mint.key(),
+                amount,
+            });
+            Ok(())
         }
         None => Err(MarketPoolError::AssetMint.into()),
     }
@@ -53,6 +64,14 @@ pub struct MarketPoolMintTo<'info> {
     // Payer / Liquidity Provider
     #[account(mut)]
     pub payer: Signer<'info>,
+    /// Game admin, only they can mint new tokens into the pool
+    pub admin: Signer<'info>,
+    #[account(
+        seeds = [seeds::GAME],
+        bump,
+        has_one = admin @ GameErrorCode::NotAdmin
+    )]
+    pub game_config: Account<'info, GameConfig>,
     /// System Program: Required for creating the Market Pool's token account
     /// for the asset being deposited into the pool
     pub system_program: Program<'info, System>,
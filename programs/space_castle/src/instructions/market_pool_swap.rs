@@ -1,4 +1,7 @@
-use crate::{error::*, market_pool::*, resource::ResourceAuthority, seeds};
+use crate::{
+    error::*, event::MarketPoolSwapped, market_pool::*, resource::ResourceAuthority, seeds,
+    utilities::convert_to_float,
+};
 use anchor_lang::prelude::*;
 use anchor_spl::{
     associated_token::AssociatedToken,
@@ -33,7 +36,7 @@ pub fn market_pool_swap(
         ctx.accounts.payer_receive_token_account.as_ref(),
     );
 
-    pool.process_swap(
+    let receive_amount = pool.process_swap(
         receive,
         pay,
         &ctx.accounts.payer,
@@ -43,7 +46,18 @@ pub fn market_pool_swap(
         ),
         pay_in_resource,
         &ctx.accounts.token_program,
-    )
+    )?;
+
+    emit!(MarketPoolSwapped {
+        payer: ctx.accounts.payer.key(),
+        pay_mint: ctx.accounts.pay_mint.key(),
+        pay_amount: amount_to_swap,
+        receive_mint: ctx.accounts.receive_mint.key(),
+        receive_amount,
+        price: convert_to_float(receive_amount, ctx.accounts.receive_mint.decimals)
+            / convert_to_float(amount_to_swap, ctx.accounts.pay_mint.decimals),
+    });
+    Ok(())
 }
 
 #[derive(Accounts)]
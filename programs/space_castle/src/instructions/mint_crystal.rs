@@ -1,13 +1,13 @@
 use anchor_lang::prelude::*;
 use anchor_spl::{
-    token::{self, Burn, Mint, MintTo, Token, TokenAccount}, 
+    token::{self, Mint, MintTo, Token, TokenAccount}, 
     metadata::{
         create_metadata_accounts_v3,
         mpl_token_metadata::types::DataV2,
         CreateMetadataAccountsV3, Metadata,
     },
 };
-use crate::{mint_decimals, resource::ResourceAuthority, seeds};
+use crate::{game::{GameConfig, GameErrorCode}, mint_decimals, resource::ResourceAuthority, seeds};
 
 pub fn mint_init_crystal(ctx: Context<MintInitCrystal>) -> Result<()> {
     let signer_seeds: &[&[&[u8]]] = &[&[seeds::MINT_CRYSTAL, &[ctx.bumps.mint]]];
@@ -112,6 +112,14 @@ pub struct MintInitCrystal<'info> {
 pub struct MintCrystal<'info> {
     #[account(mut)]
     pub payer: Signer<'info>,
+    /// Game admin, only they can mint new tokens
+    pub admin: Signer<'info>,
+    #[account(
+        seeds = [seeds::GAME],
+        bump,
+        has_one = admin @ GameErrorCode::NotAdmin
+    )]
+    pub game_config: Account<'info, GameConfig>,
     #[account(
         mut,
         seeds = [seeds::RESOURCE_AUTHORITY],
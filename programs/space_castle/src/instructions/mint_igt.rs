@@ -5,7 +5,7 @@ use anchor_spl::{
     }, token::{self, Burn, Mint, MintTo, Token, TokenAccount}
 };
 
-use crate::{mint_decimals, seeds};
+use crate::{game::{GameConfig, GameErrorCode}, mint_decimals, seeds};
 
 pub fn mint_init_igt(ctx: Context<MintInitIGT>) -> Result<()> {
     let signer_seeds: &[&[&[u8]]] = &[&[seeds::MINT_IGT, &[ctx.bumps.mint]]];
@@ -77,7 +77,7 @@ pub fn process_burn_igt<'info>(
         from,
         mint,
         authority
-    ): (&Account<'info, TokenAccount>, &Account<'info, Mint>, &Account<'info, Mint>),
+    ): (&Account<'info, TokenAccount>, &Account<'info, Mint>, &Signer<'info>),
     amount: u64
 ) -> Result<()>{
     let cpi_accounts = Burn {
@@ -118,6 +118,14 @@ pub struct MintInitIGT<'info> {
 pub struct MintIGT<'info> {
     #[account(mut)]
     pub payer: Signer<'info>,
+    /// Game admin, only they can mint new tokens
+    pub admin: Signer<'info>,
+    #[account(
+        seeds = [seeds::GAME],
+        bump,
+        has_one = admin @ GameErrorCode::NotAdmin
+    )]
+    pub game_config: Account<'info, GameConfig>,
     #[account(
         mut,
         seeds = [seeds::MINT_IGT],
@@ -5,10 +5,10 @@ use anchor_spl::{
         mpl_token_metadata::types::DataV2,
         CreateMetadataAccountsV3, Metadata,
     }, 
-    token::{self, Burn, Mint, MintTo, Token, TokenAccount},
+    token::{self, Mint, MintTo, Token, TokenAccount},
 };
 
-use crate::{mint_decimals, resource::ResourceAuthority, seeds};
+use crate::{game::{GameConfig, GameErrorCode}, mint_decimals, resource::ResourceAuthority, seeds};
 
 pub fn mint_init_metal(ctx: Context<MintInitMetal>) -> Result<()> {
     let signer_seeds: &[&[&[u8]]] = &[&[seeds::MINT_METAL, &[ctx.bumps.mint]]];
@@ -114,6 +114,14 @@ pub struct MintInitMetal<'info> {
 pub struct MintMetal<'info> {
     #[account(mut)]
     pub payer: Signer<'info>,
+    /// Game admin, only they can mint new tokens
+    pub admin: Signer<'info>,
+    #[account(
+        seeds = [seeds::GAME],
+        bump,
+        has_one = admin @ GameErrorCode::NotAdmin
+    )]
+    pub game_config: Account<'info, GameConfig>,
     #[account(
         mut,
         seeds = [seeds::RESOURCE_AUTHORITY],
@@ -1,6 +1,16 @@
+pub mod balance_config_initialize;
+pub mod balance_config_update;
+pub mod battle_report_close;
+pub mod fleet_arrive;
 pub mod fleet_attack;
+pub mod fleet_disband;
+pub mod fleet_merge;
 pub mod fleet_move;
 pub mod fleet_new;
+pub mod fleet_refit;
+pub mod fleet_salvage;
+pub mod fleet_split;
+pub mod initialize_game;
 pub mod market_pool_create;
 pub mod market_pool_fund;
 pub mod market_pool_mint;
@@ -12,16 +22,31 @@ pub mod mint_igt;
 pub mod mint_metal;
 pub mod planet_building_change;
 pub mod planet_building_new;
+pub mod planet_building_repair;
 pub mod planet_building_upgrade;
+pub mod planet_colonize;
 pub mod planet_first_claim;
 pub mod planet_harvest;
+pub mod planet_invade;
 pub mod player_claim_resource_cache;
 pub mod player_create_resource_accounts;
 pub mod player_register;
+pub mod set_game_admin;
+pub mod ship_design_save;
 
+pub use balance_config_initialize::*;
+pub use balance_config_update::*;
+pub use battle_report_close::*;
+pub use fleet_arrive::*;
 pub use fleet_attack::*;
+pub use fleet_disband::*;
+pub use fleet_merge::*;
 pub use fleet_move::*;
 pub use fleet_new::*;
+pub use fleet_refit::*;
+pub use fleet_salvage::*;
+pub use fleet_split::*;
+pub use initialize_game::*;
 pub use market_pool_create::*;
 pub use market_pool_fund::*;
 pub use market_pool_mint::*;
@@ -33,9 +58,14 @@ pub use mint_igt::*;
 pub use mint_metal::*;
 pub use planet_building_change::*;
 pub use planet_building_new::*;
+pub use planet_building_repair::*;
 pub use planet_building_upgrade::*;
+pub use planet_colonize::*;
 pub use planet_first_claim::*;
 pub use planet_harvest::*;
+pub use planet_invade::*;
 pub use player_claim_resource_cache::*;
 pub use player_create_resource_accounts::*;
 pub use player_register::*;
+pub use set_game_admin::*;
+pub use ship_design_save::*;
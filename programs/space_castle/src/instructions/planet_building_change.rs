@@ -1,9 +1,11 @@
 use anchor_lang::prelude::*;
 use anchor_spl::token::{ Mint, Token, TokenAccount };
-use crate::{building::{Building, BuildingErrorCode, BuildingType}, planet::*,  resource::{burn_resources, ResourceAuthority}, seeds };
+use crate::{balance::BalanceConfig, building::{Building, BuildingErrorCode, BuildingType}, event::BuildingChanged, planet::*,  resource::{burn_resources, ResourceAuthority}, seeds };
 
 pub fn planet_building_change(
     ctx: Context<PlanetBuildingChange>, 
+    x: u16,
+    y: u16,
     building_type_from: BuildingType, 
     building_type_to: BuildingType
 ) -> Result<()> {
@@ -12,8 +14,18 @@ pub fn planet_building_change(
         None => Err(BuildingErrorCode::BuildingNotPresent.into()),
         Some(building) => {
             building.building_type = building_type_to;
-            let costs = building.calculate_upgrade_cost();
+            let costs = building.calculate_upgrade_cost(&ctx.accounts.balance_config.values);
             building.level = building.level.saturating_div(2).min(1);
+            building.damaged_levels = 0;
+            emit!(BuildingChanged {
+                player: ctx.accounts.signer.key(),
+                x,
+                y,
+                building_type_from,
+                building_type_to,
+                level: building.level,
+                cost: costs.clone(),
+            });
             burn_resources(
                 costs, 
                 &ctx.accounts.token_program, 
@@ -56,6 +68,8 @@ pub struct PlanetBuildingChange<'info> {
     // Resource authority
     #[account(mut, seeds = [seeds::RESOURCE_AUTHORITY], bump)]
     pub resource_authority: Account<'info, ResourceAuthority>,
+    #[account(seeds = [seeds::BALANCE_CONFIG], bump)]
+    pub balance_config: Account<'info, BalanceConfig>,
 
     // Mints
     #[account(mut, seeds = [seeds::MINT_METAL], bump)]
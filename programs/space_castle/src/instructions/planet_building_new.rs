@@ -1,8 +1,8 @@
 use anchor_lang::prelude::*;
 use anchor_spl::token::{ Mint, Token, TokenAccount };
-use crate::{building::{Building, BuildingErrorCode, BuildingType}, planet::*, resource::{burn_resources, ResourceAuthority}, seeds };
+use crate::{balance::BalanceConfig, building::{Building, BuildingErrorCode, BuildingType}, event::BuildingBuilt, planet::*, resource::{burn_resources, ResourceAuthority}, seeds };
 
-pub fn planet_building_new(ctx: Context<PlanetBuildingNew>, building_type: BuildingType) -> Result<()> {
+pub fn planet_building_new(ctx: Context<PlanetBuildingNew>, x: u16, y: u16, building_type: BuildingType) -> Result<()> {
     let mut build_spot: Option<usize> = None;
     for (index, b) in ctx.accounts.planet_holding.buildings.iter_mut().enumerate() {
         if b.building_type.eq(&building_type) {
@@ -16,10 +16,18 @@ pub fn planet_building_new(ctx: Context<PlanetBuildingNew>, building_type: Build
         let holding = &mut ctx.accounts.planet_holding;
         let new_building = Building {
             level: 1,
-            building_type
+            building_type,
+            damaged_levels: 0,
         };
         holding.buildings[build_spot] = new_building;
-        let costs = new_building.calculate_upgrade_cost();
+        let costs = new_building.calculate_upgrade_cost(&ctx.accounts.balance_config.values);
+        emit!(BuildingBuilt {
+            player: ctx.accounts.signer.key(),
+            x,
+            y,
+            building_type,
+            cost: costs.clone(),
+        });
         burn_resources(
             costs, 
             &ctx.accounts.token_program, 
@@ -65,6 +73,8 @@ pub struct PlanetBuildingNew<'info> {
     // Resource authority
     #[account(mut, seeds = [seeds::RESOURCE_AUTHORITY], bump)]
     pub resource_authority: Account<'info, ResourceAuthority>,
+    #[account(seeds = [seeds::BALANCE_CONFIG], bump)]
+    pub balance_config: Account<'info, BalanceConfig>,
 
     // Mints
     #[account(mut, seeds = [seeds::MINT_METAL], bump)]
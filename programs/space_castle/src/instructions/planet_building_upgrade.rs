@@ -1,14 +1,22 @@
 use anchor_lang::prelude::*;
 use anchor_spl::token::{ Mint, Token, TokenAccount };
-use crate::{building::{Building, BuildingErrorCode, BuildingType}, planet::*,  resource::{burn_resources, ResourceAuthority}, seeds };
+use crate::{balance::BalanceConfig, building::{Building, BuildingErrorCode, BuildingType}, event::BuildingUpgraded, planet::*,  resource::{burn_resources, ResourceAuthority}, seeds };
 
-pub fn planet_building_upgrade(ctx: Context<PlanetBuildingUpgrade>, building_type: BuildingType) -> Result<()> {
+pub fn planet_building_upgrade(ctx: Context<PlanetBuildingUpgrade>, x: u16, y: u16, building_type: BuildingType) -> Result<()> {
     let building: Option<&mut Building> = ctx.accounts.planet_holding.buildings.iter_mut().find(|b| b.building_type.eq(&building_type));
     match building {
         None => Err(BuildingErrorCode::BuildingNotPresent.into()),
         Some(building) => {
-            building.level += 1;
-            let costs = building.calculate_upgrade_cost();
+            building.upgrade();
+            let costs = building.calculate_upgrade_cost(&ctx.accounts.balance_config.values);
+            emit!(BuildingUpgraded {
+                player: ctx.accounts.signer.key(),
+                x,
+                y,
+                building_type,
+                level: building.level,
+                cost: costs.clone(),
+            });
             burn_resources(
                 costs, 
                 &ctx.accounts.token_program, 
@@ -51,6 +59,8 @@ pub struct PlanetBuildingUpgrade<'info> {
     // Resource authority
     #[account(mut, seeds = [seeds::RESOURCE_AUTHORITY], bump)]
     pub resource_authority: Account<'info, ResourceAuthority>,
+    #[account(seeds = [seeds::BALANCE_CONFIG], bump)]
+    pub balance_config: Account<'info, BalanceConfig>,
 
     // Mints
     #[account(mut, seeds = [seeds::MINT_METAL], bump)]
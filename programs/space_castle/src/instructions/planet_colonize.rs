@@ -0,0 +1,167 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{ Mint, Token, TokenAccount };
+use crate::{balance::BalanceConfig, building::{generate_initial_buildings_for_colony, BuildingType}, event::PlanetClaimed, fleet::*, planet::*, player::*, process_burn_igt, resource::{burn_resources, ResourceAuthority}, seeds};
+
+pub fn planet_colonize(ctx: Context<PlanetColonize>, x: u16, y: u16) -> Result<()> {
+    // CHECK IF PLANET ACTUALLY EXISTS
+    if !are_planet_coordinates_valid(x, y) {
+        return Err(PlanetErrorCode::NoPlanetAtCoordinates.into());
+    }
+
+    // CHECK IF PLAYER CAN HAVE ANOTHER COLONY
+    let capital_level = ctx.accounts.capital_holding.building_level(BuildingType::PlanetaryCapital);
+    if capital_level == 0 {
+        return Err(PlanetErrorCode::NoPlanetaryCapital.into());
+    }
+    let player_info = &mut ctx.accounts.player_info;
+    let colonies = player_info.settled_planets.saturating_sub(1);
+    if colonies >= capital_level {
+        return Err(PlanetErrorCode::ColonyLimitReached.into());
+    }
+    let costs = colonization_cost(player_info.settled_planets, &ctx.accounts.balance_config.values);
+    player_info.settled_planets += 1;
+
+    // LAND THE COLONISTS
+    ctx.accounts.fleet.use_landing_pods(COLONIZATION_LANDING_PODS)?;
+
+    // CREATE PLANET METADATA
+    let planet_info = &mut ctx.accounts.planet_info;
+    planet_info.settle(ctx.accounts.signer.key(), x, y);
+
+    // CREATE PLAYERS HOLDING
+    let planet_holding = &mut ctx.accounts.planet_holding;
+    planet_holding.last_harvest = Clock::get()?.slot;
+    planet_holding.buildings = generate_initial_buildings_for_colony(planet_info.resources());
+
+    emit!(PlanetClaimed {
+        player: ctx.accounts.signer.key(),
+        x,
+        y,
+        cost: costs.clone(),
+    });
+
+    // PAY FOR THE COLONY
+    burn_resources(
+        costs.clone(),
+        &ctx.accounts.token_program,
+        &ctx.accounts.resource_authority,
+        ctx.bumps.resource_authority,
+        (
+            &ctx.accounts.mint_metal,
+            &ctx.accounts.mint_crystal,
+            &ctx.accounts.mint_chemical,
+            &ctx.accounts.mint_fuel
+        ),
+        (
+            &ctx.accounts.account_metal,
+            &ctx.accounts.account_crystal,
+            &ctx.accounts.account_chemical,
+            &ctx.accounts.account_fuel
+        )
+    )?;
+    process_burn_igt(&ctx.accounts.token_program, (
+        &ctx.accounts.account_igt,
+        &ctx.accounts.mint_igt,
+        &ctx.accounts.signer
+    ), costs.igt)
+}
+
+#[derive(Accounts)]
+#[instruction(x: u16, y: u16, capital_x: u16, capital_y: u16)]
+pub struct PlanetColonize<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    #[account(
+        init,
+        seeds = [
+            seeds::PLANET_INFO,
+            x.to_le_bytes().as_ref(),
+            y.to_le_bytes().as_ref()
+        ],
+        bump,
+        payer = signer,
+        space = 8 + PlanetInfo::INIT_SPACE
+    )]
+    pub planet_info: Account<'info, PlanetInfo>,
+    #[account(
+        init,
+        seeds = [
+            seeds::PLANET_HOLDING,
+            signer.key().as_ref(),
+            x.to_le_bytes().as_ref(),
+            y.to_le_bytes().as_ref(),
+        ],
+        bump,
+        payer = signer,
+        space = 8 + PlanetHolding::INIT_SPACE
+    )]
+    pub planet_holding: Account<'info, PlanetHolding>,
+    // Holding with the Planetary Capital, its level limits the number of colonies
+    #[account(
+        seeds = [
+            seeds::PLANET_HOLDING,
+            signer.key().as_ref(),
+            capital_x.to_le_bytes().as_ref(),
+            capital_y.to_le_bytes().as_ref(),
+        ],
+        bump,
+    )]
+    pub capital_holding: Account<'info, PlanetHolding>,
+    // Fleet carrying the colonists
+    #[account(
+        mut,
+        seeds = [
+            seeds::FLEET,
+            x.to_le_bytes().as_ref(),
+            y.to_le_bytes().as_ref(),
+        ],
+        bump,
+        constraint = fleet.is_present() @ FleetErrorCode::FleetNotPresent,
+        constraint = fleet.is_owned_by(&signer.key()) @ FleetErrorCode::NoAuthority,
+        constraint = !fleet.is_in_transit() @ FleetErrorCode::FleetInTransit,
+    )]
+    pub fleet: Account<'info, Fleet>,
+    #[account(
+        mut,
+        seeds = [seeds::PLAYER, signer.key().as_ref()],
+        bump,
+    )]
+    pub player_info: Account<'info, Player>,
+    #[account(seeds = [seeds::BALANCE_CONFIG], bump)]
+    pub balance_config: Box<Account<'info, BalanceConfig>>,
+
+    // Resource authority
+    #[account(mut, seeds = [seeds::RESOURCE_AUTHORITY], bump)]
+    pub resource_authority: Account<'info, ResourceAuthority>,
+
+    // Mints
+    #[account(mut, seeds = [seeds::MINT_IGT], bump)]
+    pub mint_igt: Account<'info, Mint>,
+    #[account(mut, seeds = [seeds::MINT_METAL], bump)]
+    pub mint_metal: Account<'info, Mint>,
+    #[account(mut, seeds = [seeds::MINT_CHEMICAL], bump)]
+    pub mint_chemical: Account<'info, Mint>,
+    #[account(mut, seeds = [seeds::MINT_CRYSTAL], bump)]
+    pub mint_crystal: Account<'info, Mint>,
+    #[account(mut, seeds = [seeds::MINT_FUEL], bump)]
+    pub mint_fuel: Account<'info, Mint>,
+
+    // User resource token accounts
+    #[account(
+        mut,
+        associated_token::mint = mint_igt,
+        associated_token::authority = signer
+    )]
+    pub account_igt: Account<'info, TokenAccount>,
+    #[account(mut, seeds = [seeds::ACCOUNT_METAL, signer.key().as_ref()], bump)]
+    pub account_metal: Account<'info, TokenAccount>,
+    #[account(mut, seeds = [seeds::ACCOUNT_CRYSTAL, signer.key().as_ref()], bump)]
+    pub account_crystal: Account<'info, TokenAccount>,
+    #[account(mut, seeds = [seeds::ACCOUNT_CHEMICAL, signer.key().as_ref()], bump)]
+    pub account_chemical: Account<'info, TokenAccount>,
+    #[account(mut, seeds = [seeds::ACCOUNT_FUEL, signer.key().as_ref()], bump)]
+    pub account_fuel: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
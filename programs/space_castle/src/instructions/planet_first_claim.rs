@@ -1,5 +1,5 @@
 use anchor_lang::prelude::*;
-use crate::{building::generate_initial_buildings_for_planet, fleet::*, planet::*, player::*, resource::{PlayerCache, Resources}, seeds};
+use crate::{balance::BalanceConfig, building::generate_initial_buildings_for_planet, event::PlanetClaimed, fleet::*, planet::*, player::*, resource::{PlayerCache, Resources}, seeds};
 
 pub fn planet_first_claim(ctx: Context<PlanetFirstClaim>, x: u16, y: u16) -> Result<()> {
     // CHECK IF PLANET ACTUALLY EXISTS
@@ -12,12 +12,11 @@ pub fn planet_first_claim(ctx: Context<PlanetFirstClaim>, x: u16, y: u16) -> Res
     if planet_info.owner.is_some() {
         return Err(PlanetErrorCode::PlanetAlreadySettled.into());
     }
-    planet_info.owner = Some(*ctx.accounts.signer.key);
-    planet_info.miner = None;
+    planet_info.settle(ctx.accounts.signer.key(), x, y);
 
     // CREATE PLAYERS HOLDING
     let planet_holding = &mut ctx.accounts.planet_holding;
-    planet_holding.last_harvest = 0;
+    planet_holding.last_harvest = Clock::get()?.slot;
 
     // UPDATE PLAYER INFO
     let player_info = &mut ctx.accounts.player_info;
@@ -28,8 +27,8 @@ pub fn planet_first_claim(ctx: Context<PlanetFirstClaim>, x: u16, y: u16) -> Res
     player_info.settled_planets = 1;
 
     // Get affinity (metal = 0, crystal = 1, chemical = 2)
-    let planet_resources = get_planet_resources(x, y);
-    let planet_affinity = get_planet_affinity(planet_resources);
+    let planet_resources = ctx.accounts.planet_info.resources();
+    let planet_affinity = ctx.accounts.planet_info.planet_affinity();
 
     // Create initial buildings
     ctx.accounts.planet_holding.buildings = generate_initial_buildings_for_planet(planet_resources);
@@ -40,14 +39,14 @@ pub fn planet_first_claim(ctx: Context<PlanetFirstClaim>, x: u16, y: u16) -> Res
 
     // Give player some resources to cache
     let cache = &mut ctx.accounts.player_cache;
-    cache.resources.add(Resources {
-        igt: 10,
-        metal: 10,
-        crystal: 10,
-        chemical: 10,
-        fuel: 10
-    });
+    cache.resources.add(ctx.accounts.balance_config.values.first_planet_grant.clone());
 
+    emit!(PlanetClaimed {
+        player: ctx.accounts.signer.key(),
+        x,
+        y,
+        cost: Resources::default(),
+    });
     Ok(())
 }
 
@@ -108,5 +107,7 @@ pub struct PlanetFirstClaim<'info> {
         bump
     )]
     pub player_cache: Account<'info, PlayerCache>,
+    #[account(seeds = [seeds::BALANCE_CONFIG], bump)]
+    pub balance_config: Account<'info, BalanceConfig>,
     pub system_program: Program<'info, System>,
  }
@@ -1,19 +1,21 @@
 use anchor_lang::prelude::*;
 use anchor_spl::token::{Mint, Token, TokenAccount};
 use solana_program::clock::Clock;
-use crate::{planet::*, player::*, resource::{ResourceAuthority, Resources}, seeds};
+use crate::{balance::BalanceConfig, event::PlanetHarvested, planet::*, player::*, resource::{ResourceAuthority, Resources}, seeds};
 
-pub fn planet_harvest(ctx: Context<PlanetHarvest>, _x: u16, _y: u16) -> Result<()> {
+pub fn planet_harvest(ctx: Context<PlanetHarvest>, x: u16, y: u16) -> Result<()> {
     if !ctx.accounts.planet_info.is_owned_by(&ctx.accounts.signer.key()) {
         return Err(PlanetErrorCode::NoAuthority.into())
     } 
-    let resources = Resources {
-        igt: 10,
-        metal: 1,
-        crystal: 1,
-        chemical: 1,
-        fuel: 1
-    }; 
+    let current_slot = Clock::get()?.slot;
+    let resources = ctx.accounts.planet_holding.unclaimed_production(
+        get_planet_resources(x, y),
+        current_slot,
+        &ctx.accounts.balance_config.values
+    );
+    if resources.is_empty() {
+        return Err(PlanetErrorCode::NothingToHarvest.into())
+    }
     resources.mint(
         &ctx.accounts.token_program, 
         (
@@ -32,7 +34,15 @@ pub fn planet_harvest(ctx: Context<PlanetHarvest>, _x: u16, _y: u16) -> Result<(
     )?; 
 
     let planet_holding = &mut ctx.accounts.planet_holding;
-    planet_holding.last_harvest = Clock::get()?.slot;
+    planet_holding.last_harvest = planet_holding.harvested_until(current_slot, &ctx.accounts.balance_config.values);
+    planet_holding.plundered = Resources::default();
+
+    emit!(PlanetHarvested {
+        player: ctx.accounts.signer.key(),
+        x,
+        y,
+        resources,
+    });
 
     Ok(())
 }
@@ -53,6 +63,7 @@ pub struct PlanetHarvest<'info> {
     )]
     pub planet_info: Account<'info, PlanetInfo>,
     #[account(
+        mut,
         seeds = [
             seeds::PLANET_HOLDING,
             signer.key().as_ref(),
@@ -74,6 +85,8 @@ pub struct PlanetHarvest<'info> {
         bump
     )]
     pub resource_authority: Account<'info, ResourceAuthority>,
+    #[account(seeds = [seeds::BALANCE_CONFIG], bump)]
+    pub balance_config: Account<'info, BalanceConfig>,
     
     // Metal
     #[account(
@@ -0,0 +1,436 @@
+use crate::{
+    balance::BalanceConfig,
+    battle::{fleet_battle, BattleReport, BattleResult, BattleSide, DebrisField, PlanetDefenses},
+    building::BuildingType,
+    event::PlanetInvaded,
+    fleet::{is_adjacent, retreat_into_fallback, Fleet, FleetErrorCode},
+    planet::{PlanetErrorCode, PlanetHolding, PlanetInfo, COLONIZATION_LANDING_PODS},
+    player::Player,
+    resource::{process_burn_resource, PlayerCache, ResourceAuthority, Resources},
+    reward::BattleRewards,
+    seeds,
+};
+use anchor_lang::{prelude::*, system_program};
+use anchor_spl::token::{Mint, Token, TokenAccount};
+
+pub fn planet_invade(
+    ctx: Context<PlanetInvade>,
+    x: u16,
+    y: u16,
+    target_x: u16,
+    target_y: u16,
+    retreat_x: u16,
+    retreat_y: u16,
+) -> Result<()> {
+    if ctx.accounts.fleet.landing_pods() < COLONIZATION_LANDING_PODS as u32 {
+        return Err(FleetErrorCode::NotEnoughLandingPods.into());
+    }
+    if !is_adjacent((x, y), (target_x, target_y)) {
+        return Err(FleetErrorCode::NotAdjacent.into());
+    }
+    if ctx
+        .accounts
+        .planet_holding
+        .building_level(BuildingType::PlanetaryCapital)
+        > 0
+    {
+        return Err(PlanetErrorCode::CapitalCannotBeCaptured.into());
+    }
+
+    // Captured planets count towards the colony limit
+    let capital_level = ctx
+        .accounts
+        .capital_holding
+        .building_level(BuildingType::PlanetaryCapital);
+    if capital_level == 0 {
+        return Err(PlanetErrorCode::NoPlanetaryCapital.into());
+    }
+    if ctx.accounts.player_info.settled_planets.saturating_sub(1) >= capital_level {
+        return Err(PlanetErrorCode::ColonyLimitReached.into());
+    }
+
+    let balance = &ctx.accounts.balance_config.values;
+    let slot = Clock::get()?.slot;
+    let signer = ctx.accounts.signer.key();
+    let defender = ctx.accounts.defender.key();
+    let previous_owner = ctx.accounts.previous_owner.key();
+    let fleet = &mut ctx.accounts.fleet;
+    let fleet_target = &mut ctx.accounts.fleet_target;
+
+    // Defending fleet and the planet's defense buildings have to be beaten before landing
+    let mut planet =
+        PlanetDefenses::from_buildings(&ctx.accounts.planet_holding.buildings, balance);
+    let defended = fleet_target.is_present() || planet.is_standing();
+    let mut debris = Resources::default();
+    let battle_result = if defended {
+        fleet.recover_morale(slot, balance.morale_recovery_slots);
+        fleet_target.recover_morale(slot, balance.morale_recovery_slots);
+        let battle_result = fleet_battle(fleet, fleet_target, &mut planet, balance);
+        fleet.settle_after_battle(slot);
+        fleet_target.settle_after_battle(slot);
+        debris = ctx
+            .accounts
+            .debris_field
+            .add_losses(&battle_result, balance);
+        BattleRewards::from_result(&battle_result).credit(
+            &mut ctx.accounts.player_cache,
+            &mut ctx.accounts.defender_cache,
+        );
+        let attacker_won = matches!(battle_result.winner, BattleSide::Attacker);
+        ctx.accounts.player_info.record_battle(attacker_won);
+        ctx.accounts.defender_info.record_battle(!attacker_won);
+        battle_result
+    } else {
+        BattleResult::uncontested()
+    };
+    // A battle that runs out of rounds goes to the attacker, but the planet can only be taken
+    // once the defending fleet is beaten as well
+    let defenders_defeated =
+        matches!(battle_result.winner, BattleSide::Attacker) && fleet_target.in_retreat();
+
+    // Keep the report of the invasion, like for attacks
+    let battle_id = ctx.accounts.player_info.open_battle_report();
+    ctx.accounts.battle_report.record(
+        battle_id,
+        (signer, defender),
+        ((x, y), (target_x, target_y)),
+        &battle_result,
+    )?;
+    let fuel_cost = fleet.get_move_quote((x, y), (target_x, target_y));
+
+    // Survivors of the defending fleet fall back, to the other positions around the planet in
+    // the remaining accounts when the retreat is blocked. Without any room left they keep the
+    // planet's orbit occupied and the invasion fails
+    if defenders_defeated && !fleet_target.retreat_into(&mut ctx.accounts.fleet_retreat, slot) {
+        retreat_into_fallback(
+            fleet_target,
+            ((x, y), (target_x, target_y), (retreat_x, retreat_y)),
+            ctx.remaining_accounts,
+            slot,
+        )?;
+    }
+
+    // Landing pods that survived the battle take over the planet
+    let captured = defenders_defeated
+        && !fleet_target.is_present()
+        && fleet.landing_pods() >= COLONIZATION_LANDING_PODS as u32;
+    if captured {
+        fleet.use_landing_pods(COLONIZATION_LANDING_PODS)?;
+        if fleet.is_present() && fleet_target.is_free(slot) {
+            fleet_target.replace_with_another_fleet(fleet);
+            fleet.reset();
+        }
+
+        // Transfer the planet, the new holding is only paid for once the planet is taken
+        ctx.accounts.planet_info.owner = Some(signer);
+        let space = 8 + PlanetHolding::INIT_SPACE;
+        let rent = Rent::get()?.minimum_balance(space);
+        let system_program = ctx.accounts.system_program.to_account_info();
+        let payer = ctx.accounts.signer.to_account_info();
+        let new_holding = ctx.accounts.new_planet_holding.to_account_info();
+        let holding_seeds: &[&[&[u8]]] = &[&[
+            seeds::PLANET_HOLDING,
+            signer.as_ref(),
+            &target_x.to_le_bytes(),
+            &target_y.to_le_bytes(),
+            &[ctx.bumps.new_planet_holding],
+        ]];
+        if new_holding.lamports() == 0 {
+            system_program::create_account(
+                CpiContext::new_with_signer(
+                    system_program,
+                    system_program::CreateAccount {
+                        from: payer,
+                        to: new_holding,
+                    },
+                    holding_seeds,
+                ),
+                rent,
+                space as u64,
+                &crate::ID,
+            )?;
+        } else {
+            // Anyone can send lamports to the address and make `create_account` fail, so an
+            // already funded account is topped up to rent and claimed instead
+            let additional_rent_to_fund = rent.saturating_sub(new_holding.lamports());
+            if additional_rent_to_fund > 0 {
+                system_program::transfer(
+                    CpiContext::new(
+                        system_program.clone(),
+                        system_program::Transfer {
+                            from: payer,
+                            to: new_holding.clone(),
+                        },
+                    ),
+                    additional_rent_to_fund,
+                )?;
+            }
+            system_program::allocate(
+                CpiContext::new_with_signer(
+                    system_program.clone(),
+                    system_program::Allocate {
+                        account_to_allocate: new_holding.clone(),
+                    },
+                    holding_seeds,
+                ),
+                space as u64,
+            )?;
+            system_program::assign(
+                CpiContext::new_with_signer(
+                    system_program,
+                    system_program::Assign {
+                        account_to_assign: new_holding,
+                    },
+                    holding_seeds,
+                ),
+                &crate::ID,
+            )?;
+        }
+        PlanetHolding {
+            last_harvest: slot,
+            buildings: ctx.accounts.planet_holding.buildings,
+            plundered: Resources::default(),
+        }
+        .save_to(&ctx.accounts.new_planet_holding.to_account_info())?;
+        ctx.accounts.player_info.settled_planets += 1;
+
+        // Player info of the previous owner is only loaded on its own when a third player's
+        // fleet defended the planet
+        if defender == previous_owner {
+            let defender_info = &mut ctx.accounts.defender_info;
+            defender_info.settled_planets = defender_info.settled_planets.saturating_sub(1);
+        } else {
+            let account = ctx.accounts.previous_owner_info.to_account_info();
+            let mut previous_owner_info = Player::try_deserialize(&mut &account.data.borrow()[..])?;
+            previous_owner_info.settled_planets =
+                previous_owner_info.settled_planets.saturating_sub(1);
+            previous_owner_info.try_serialize(&mut &mut account.try_borrow_mut_data()?[..])?;
+        }
+
+        // Previous holding is seeded by the previous owner, return its rent to them
+        ctx.accounts
+            .planet_holding
+            .close(ctx.accounts.previous_owner.to_account_info())?;
+    }
+
+    emit!(PlanetInvaded {
+        battle_id,
+        attacker: signer,
+        defender,
+        previous_owner,
+        target_x,
+        target_y,
+        captured,
+        debris,
+    });
+
+    // Burn fuel of the attacker
+    process_burn_resource(
+        &ctx.accounts.token_program,
+        (
+            &ctx.accounts.account_fuel,
+            &ctx.accounts.mint_fuel,
+            (
+                &ctx.accounts.resource_authority,
+                ctx.bumps.resource_authority,
+            ),
+        ),
+        fuel_cost,
+    )
+}
+
+#[derive(Accounts)]
+#[instruction(
+    x: u16,
+    y: u16,
+    target_x: u16,
+    target_y: u16,
+    capital_x: u16,
+    capital_y: u16,
+    retreat_x: u16,
+    retreat_y: u16
+)]
+pub struct PlanetInvade<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    // Invading fleet
+    #[account(
+        mut,
+        seeds = [
+            seeds::FLEET,
+            x.to_le_bytes().as_ref(),
+            y.to_le_bytes().as_ref()
+        ],
+        bump,
+        constraint = fleet.is_present() @ FleetErrorCode::FleetNotPresent,
+        constraint = fleet.is_owned_by(&signer.key()) @ FleetErrorCode::NoAuthority,
+        constraint = !fleet.is_in_transit() @ FleetErrorCode::FleetInTransit,
+    )]
+    pub fleet: Box<Account<'info, Fleet>>,
+    // Fleet defending the planet, its account may have been closed when it was disbanded
+    #[account(
+        init_if_needed,
+        payer = signer,
+        space = 8 + Fleet::INIT_SPACE,
+        seeds = [
+            seeds::FLEET,
+            target_x.to_le_bytes().as_ref(),
+            target_y.to_le_bytes().as_ref()
+        ],
+        bump,
+        constraint = !fleet_target.is_owned_by(&signer.key()) @ FleetErrorCode::NoAuthority,
+        constraint = !fleet_target.is_in_transit() @ FleetErrorCode::FleetInTransit,
+    )]
+    pub fleet_target: Box<Account<'info, Fleet>>,
+    // Where the defending fleet falls back to if it loses
+    #[account(
+        init_if_needed,
+        payer = signer,
+        space = 8 + Fleet::INIT_SPACE,
+        seeds = [
+            seeds::FLEET,
+            retreat_x.to_le_bytes().as_ref(),
+            retreat_y.to_le_bytes().as_ref()
+        ],
+        bump,
+        constraint = is_adjacent((target_x, target_y), (retreat_x, retreat_y)) @ FleetErrorCode::InvalidRetreat,
+        constraint = (retreat_x, retreat_y) != (x, y) @ FleetErrorCode::InvalidRetreat,
+    )]
+    pub fleet_retreat: Box<Account<'info, Fleet>>,
+    // Wreckage of the battle
+    #[account(
+        init_if_needed,
+        payer = signer,
+        space = 8 + DebrisField::INIT_SPACE,
+        seeds = [
+            seeds::DEBRIS_FIELD,
+            target_x.to_le_bytes().as_ref(),
+            target_y.to_le_bytes().as_ref()
+        ],
+        bump,
+    )]
+    pub debris_field: Box<Account<'info, DebrisField>>,
+    // Planet
+    #[account(
+        mut,
+        seeds = [
+            seeds::PLANET_INFO,
+            target_x.to_le_bytes().as_ref(),
+            target_y.to_le_bytes().as_ref()
+        ],
+        bump,
+        constraint = planet_info.is_owned_by(&previous_owner.key()) @ PlanetErrorCode::NoAuthority,
+        constraint = !planet_info.is_owned_by(&signer.key()) @ PlanetErrorCode::PlanetAlreadySettled,
+    )]
+    pub planet_info: Box<Account<'info, PlanetInfo>>,
+    #[account(
+        mut,
+        seeds = [
+            seeds::PLANET_HOLDING,
+            previous_owner.key().as_ref(),
+            target_x.to_le_bytes().as_ref(),
+            target_y.to_le_bytes().as_ref(),
+        ],
+        bump,
+    )]
+    pub planet_holding: Box<Account<'info, PlanetHolding>>,
+    /// CHECK: Attacker's holding of the planet, only created when the planet is captured
+    #[account(
+        mut,
+        seeds = [
+            seeds::PLANET_HOLDING,
+            signer.key().as_ref(),
+            target_x.to_le_bytes().as_ref(),
+            target_y.to_le_bytes().as_ref(),
+        ],
+        bump,
+    )]
+    pub new_planet_holding: UncheckedAccount<'info>,
+    // Holding with the Planetary Capital, its level limits the number of colonies
+    #[account(
+        seeds = [
+            seeds::PLANET_HOLDING,
+            signer.key().as_ref(),
+            capital_x.to_le_bytes().as_ref(),
+            capital_y.to_le_bytes().as_ref(),
+        ],
+        bump,
+    )]
+    pub capital_holding: Box<Account<'info, PlanetHolding>>,
+    /// CHECK: Owner of the planet, checked against `planet_info`
+    #[account(mut)]
+    pub previous_owner: UncheckedAccount<'info>,
+    /// CHECK: Player info of the previous owner, the same account as `defender_info` unless a
+    /// third player's fleet defends the planet
+    #[account(
+        mut,
+        seeds = [seeds::PLAYER, previous_owner.key().as_ref()],
+        bump,
+    )]
+    pub previous_owner_info: UncheckedAccount<'info>,
+    /// CHECK: Player defending the planet, owner of the fleet there or the planet's owner
+    #[account(
+        constraint = defender.key() == fleet_target.defender_or(previous_owner.key()) @ FleetErrorCode::NoAuthority,
+    )]
+    pub defender: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [seeds::PLAYER, defender.key().as_ref()],
+        bump,
+    )]
+    pub defender_info: Box<Account<'info, Player>>,
+    #[account(
+        mut,
+        seeds = [seeds::PLAYER, signer.key().as_ref()],
+        bump,
+    )]
+    pub player_info: Box<Account<'info, Player>>,
+    // Report of the invasion, numbered per attacker
+    #[account(
+        init,
+        seeds = [
+            seeds::BATTLE_REPORT,
+            signer.key().as_ref(),
+            player_info.battle_reports.to_le_bytes().as_ref()
+        ],
+        bump,
+        space = 8 + BattleReport::INIT_SPACE,
+        payer = signer,
+    )]
+    pub battle_report: Box<Account<'info, BattleReport>>,
+    // Resource authority
+    #[account(mut, seeds = [seeds::RESOURCE_AUTHORITY], bump)]
+    pub resource_authority: Box<Account<'info, ResourceAuthority>>,
+    #[account(seeds = [seeds::BALANCE_CONFIG], bump)]
+    pub balance_config: Box<Account<'info, BalanceConfig>>,
+
+    // Player Resource caches - we use this to minimize transaction size
+    #[account(
+        mut,
+        seeds = [
+           seeds::PLAYER_CACHE,
+           signer.key().as_ref()
+        ],
+        bump,
+    )]
+    pub player_cache: Box<Account<'info, PlayerCache>>,
+    #[account(
+        mut,
+        seeds = [
+           seeds::PLAYER_CACHE,
+           defender.key().as_ref()
+        ],
+        bump,
+    )]
+    pub defender_cache: Box<Account<'info, PlayerCache>>,
+
+    // Fuel
+    #[account(mut, seeds = [seeds::MINT_FUEL], bump)]
+    pub mint_fuel: Box<Account<'info, Mint>>,
+    #[account(mut, seeds = [seeds::ACCOUNT_FUEL, signer.key().as_ref()], bump)]
+    pub account_fuel: Box<Account<'info, TokenAccount>>,
+
+    // Programs
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
@@ -1,6 +1,6 @@
 use anchor_lang::prelude::*;
 use anchor_spl::token::{Mint, Token, TokenAccount};
-use crate::{resource::{PlayerCache, ResourceAuthority}, seeds};
+use crate::{event::PlayerResourceCacheClaimed, resource::{PlayerCache, ResourceAuthority}, seeds};
 
 pub fn player_claim_resource_cache(ctx: Context<PlayerClaimResourceCache>) -> Result<()> {
     let player_cache = &mut ctx.accounts.player_cache.resources;
@@ -19,6 +19,10 @@ pub fn player_claim_resource_cache(ctx: Context<PlayerClaimResourceCache>) -> Re
         &ctx.accounts.account_chemical,
         &ctx.accounts.account_fuel,
     ))?;
+    emit!(PlayerResourceCacheClaimed {
+        player: ctx.accounts.signer.key(),
+        resources: player_cache.clone(),
+    });
     player_cache.reset();
     Ok(())
 }
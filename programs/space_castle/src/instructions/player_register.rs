@@ -3,17 +3,21 @@ use anchor_spl::{
     token::{Mint, Token, TokenAccount}, 
     associated_token::AssociatedToken,
 };
-use crate::{player::{Player, PlayerErrorCode}, process_mint_igt, resource::PlayerCache, seeds};
+use crate::{balance::BalanceConfig, event::PlayerRegistered, player::{Player, PlayerErrorCode}, process_mint_igt, resource::PlayerCache, seeds};
 
 pub fn player_register(ctx: Context<PlayerRegister>, player_name: String) -> Result<()> {
-    if player_name.as_bytes().len() > 32 {
+    if player_name.len() > 32 {
         return Err(PlayerErrorCode::NameTooLong.into());
     }
     
     // Set player information
     let player_info = &mut ctx.accounts.player;
-    player_info.name = player_name;
+    player_info.name = player_name.clone();
     player_info.settled_planets = 0;
+    emit!(PlayerRegistered {
+        player: ctx.accounts.signer.key(),
+        name: player_name,
+    });
     
     // Credit some IGT to the player
     process_mint_igt(
@@ -23,7 +27,7 @@ pub fn player_register(ctx: Context<PlayerRegister>, player_name: String) -> Res
             (&ctx.accounts.mint_igt, ctx.bumps.mint_igt), 
             &ctx.accounts.mint_igt
         ), 
-        10
+        ctx.accounts.balance_config.values.registration_igt
     )
 }
 
@@ -64,6 +68,8 @@ pub struct PlayerRegister<'info> {
         bump
     )]
     pub player_cache: Account<'info, PlayerCache>,
+    #[account(seeds = [seeds::BALANCE_CONFIG], bump)]
+    pub balance_config: Account<'info, BalanceConfig>,
     pub token_program: Program<'info, Token>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
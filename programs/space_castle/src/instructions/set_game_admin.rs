@@ -0,0 +1,24 @@
+use crate::{
+    event::GameAdminChanged,
+    game::{GameConfig, GameErrorCode},
+    seeds,
+};
+use anchor_lang::prelude::*;
+
+pub fn set_game_admin(ctx: Context<SetGameAdmin>, new_admin: Pubkey) -> Result<()> {
+    ctx.accounts.game_config.admin = new_admin;
+    emit!(GameAdminChanged { admin: new_admin });
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetGameAdmin<'info> {
+    pub admin: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [seeds::GAME],
+        bump,
+        has_one = admin @ GameErrorCode::NotAdmin
+    )]
+    pub game_config: Account<'info, GameConfig>,
+}
@@ -0,0 +1,50 @@
+use crate::{
+    event::ShipDesignSaved,
+    fleet::{validate_ship_template, FleetErrorCode, ShipDesign, ShipTemplate},
+    seeds,
+};
+use anchor_lang::prelude::*;
+
+pub fn ship_design_save(
+    ctx: Context<ShipDesignSave>,
+    index: u16,
+    name: String,
+    template: ShipTemplate,
+) -> Result<()> {
+    if name.len() > 32 {
+        return Err(FleetErrorCode::DesignNameTooLong.into());
+    }
+    validate_ship_template(&template)?;
+    let design = &mut ctx.accounts.ship_design;
+    design.owner = ctx.accounts.signer.key();
+    design.index = index;
+    design.name = name.clone();
+    design.template = template;
+    emit!(ShipDesignSaved {
+        owner: ctx.accounts.signer.key(),
+        index,
+        name,
+    });
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(index: u16)]
+pub struct ShipDesignSave<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    // Saving to an existing index overwrites the design
+    #[account(
+        init_if_needed,
+        payer = signer,
+        seeds = [
+            seeds::SHIP_DESIGN,
+            signer.key().as_ref(),
+            index.to_le_bytes().as_ref(),
+        ],
+        bump,
+        space = 8 + ShipDesign::INIT_SPACE
+    )]
+    pub ship_design: Account<'info, ShipDesign>,
+    pub system_program: Program<'info, System>,
+}
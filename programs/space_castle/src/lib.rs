@@ -1,15 +1,20 @@
-mod battle;
+pub mod balance;
+pub mod battle;
 mod building;
 mod error;
-mod fleet;
+mod event;
+pub mod fleet;
+mod game;
 pub mod instructions;
 mod market_pool;
 mod planet;
 mod player;
-mod resource;
+pub mod resource;
+mod reward;
 mod utilities;
 
 use anchor_lang::prelude::*;
+use balance::BalanceValues;
 use building::BuildingType;
 use instructions::*;
 
@@ -33,7 +38,11 @@ pub mod seeds {
     pub const PLANET_INFO: &[u8] = b"planet_info";
     pub const PLANET_HOLDING: &[u8] = b"planet_holding";
     pub const FLEET: &[u8] = b"fleet";
+    pub const BATTLE_REPORT: &[u8] = b"battle_report";
+    pub const DEBRIS_FIELD: &[u8] = b"debris_field";
+    pub const SHIP_DESIGN: &[u8] = b"ship_design";
     pub const GAME: &[u8] = b"game";
+    pub const BALANCE_CONFIG: &[u8] = b"balance_config";
     pub const RESOURCE_AUTHORITY: &[u8] = b"resource_authority";
     pub const MINT_IGT: &[u8] = b"mint_igt";
     pub const MINT_METAL: &[u8] = b"mint_metal";
@@ -61,6 +70,28 @@ pub mod seeds {
 mod space_castle {
     use super::*;
 
+    /// Game
+    ///
+    /// Game - Creates the game config, only callable by the program upgrade authority
+    pub fn initialize_game(ctx: Context<InitializeGame>, admin: Pubkey) -> Result<()> {
+        instructions::initialize_game(ctx, admin)
+    }
+    /// Game - Hands the admin authority over to a new key
+    pub fn set_game_admin(ctx: Context<SetGameAdmin>, new_admin: Pubkey) -> Result<()> {
+        instructions::set_game_admin(ctx, new_admin)
+    }
+    /// Game - Creates the balance config with default values
+    pub fn balance_config_initialize(ctx: Context<BalanceConfigInitialize>) -> Result<()> {
+        instructions::balance_config_initialize(ctx)
+    }
+    /// Game - Replaces balance values used by the game formulas
+    pub fn balance_config_update(
+        ctx: Context<BalanceConfigUpdate>,
+        values: BalanceValues,
+    ) -> Result<()> {
+        instructions::balance_config_update(ctx, values)
+    }
+
     /// Player
     ///
     /// Player - Registers a player
@@ -100,48 +131,102 @@ mod space_castle {
     pub fn planet_harvest(ctx: Context<PlanetHarvest>, x: u16, y: u16) -> Result<()> {
         instructions::planet_harvest(ctx, x, y)
     }
+    /// Planet - Colonize a planet at (x, y) with a fleet carrying landing pods
+    pub fn planet_colonize(
+        ctx: Context<PlanetColonize>,
+        x: u16,
+        y: u16,
+        _capital_x: u16,
+        _capital_y: u16,
+    ) -> Result<()> {
+        instructions::planet_colonize(ctx, x, y)
+    }
+    /// Planet - Invade a planet at (target_x, target_y) with an adjacent fleet carrying landing
+    /// pods. A beaten defending fleet falls back to (retreat_x, retreat_y), or to the first
+    /// free position around the planet in the remaining accounts when it is blocked.
+    #[allow(clippy::too_many_arguments)]
+    pub fn planet_invade(
+        ctx: Context<PlanetInvade>,
+        x: u16,
+        y: u16,
+        target_x: u16,
+        target_y: u16,
+        _capital_x: u16,
+        _capital_y: u16,
+        retreat_x: u16,
+        retreat_y: u16,
+    ) -> Result<()> {
+        instructions::planet_invade(ctx, x, y, target_x, target_y, retreat_x, retreat_y)
+    }
     /// Planet - build a new building
     pub fn planet_building_new(
         ctx: Context<PlanetBuildingNew>,
-        _x: u16,
-        _y: u16,
+        x: u16,
+        y: u16,
         building_type: BuildingType,
     ) -> Result<()> {
-        instructions::planet_building_new(ctx, building_type)
+        instructions::planet_building_new(ctx, x, y, building_type)
     }
     /// Planet - upgrade a building
     pub fn planet_building_upgrade(
         ctx: Context<PlanetBuildingUpgrade>,
-        _x: u16,
-        _y: u16,
+        x: u16,
+        y: u16,
         building_type: BuildingType,
     ) -> Result<()> {
-        instructions::planet_building_upgrade(ctx, building_type)
+        instructions::planet_building_upgrade(ctx, x, y, building_type)
     }
     /// Planet - change a building
     pub fn planet_building_change(
         ctx: Context<PlanetBuildingChange>,
-        _x: u16,
-        _y: u16,
+        x: u16,
+        y: u16,
         building_type_from: BuildingType,
         building_type_to: BuildingType,
     ) -> Result<()> {
-        instructions::planet_building_change(ctx, building_type_from, building_type_to)
+        instructions::planet_building_change(ctx, x, y, building_type_from, building_type_to)
+    }
+    /// Planet - repair levels a building lost to bombardment
+    pub fn planet_building_repair(
+        ctx: Context<PlanetBuildingRepair>,
+        x: u16,
+        y: u16,
+        building_type: BuildingType,
+    ) -> Result<()> {
+        instructions::planet_building_repair(ctx, x, y, building_type)
     }
 
     ///
     /// Fleet
     ///
-    /// Create a new fleet at (x, y)
+    /// Create a new fleet at (x, y) or reinforce the fleet parked there
     pub fn fleet_new(
         ctx: Context<FleetNew>,
-        _x: u16,
-        _y: u16,
+        x: u16,
+        y: u16,
         template: [Option<fleet::SquadronBlueprint>; fleet::SQUADRONS_IN_FLEET],
     ) -> Result<()> {
-        instructions::fleet_new(ctx, template)
+        instructions::fleet_new(ctx, x, y, template)
     }
-    /// Move a fleet to (x, y)
+    /// Create or reinforce a fleet at (x, y) from saved ship designs
+    pub fn fleet_new_from_designs(
+        ctx: Context<FleetNew>,
+        x: u16,
+        y: u16,
+        squadrons: [Option<fleet::DesignedSquadron>; fleet::SQUADRONS_IN_FLEET],
+    ) -> Result<()> {
+        instructions::fleet_new_from_designs(ctx, x, y, squadrons)
+    }
+    /// Save a ship template under `index` so fleets can be built from it
+    pub fn ship_design_save(
+        ctx: Context<ShipDesignSave>,
+        index: u16,
+        name: String,
+        template: fleet::ShipTemplate,
+    ) -> Result<()> {
+        instructions::ship_design_save(ctx, index, name, template)
+    }
+    /// Send a fleet to (move_x, move_y), it arrives after a number of slots
     pub fn fleet_move(
         ctx: Context<FleetMove>,
         x: u16,
@@ -151,15 +236,86 @@ mod space_castle {
     ) -> Result<()> {
         instructions::fleet_move(ctx, x, y, move_x, move_y)
     }
-    /// Attack a fleet or planet at (x, y)
+    /// Land a fleet that finished travelling from (x, y) to (to_x, to_y), callable by anyone
+    pub fn fleet_arrive(
+        ctx: Context<FleetArrive>,
+        x: u16,
+        y: u16,
+        to_x: u16,
+        to_y: u16,
+    ) -> Result<()> {
+        instructions::fleet_arrive(ctx, x, y, to_x, to_y)
+    }
+    /// Refit a squadron of the fleet at (x, y) with a new ship template at the planet's Shipyard
+    pub fn fleet_refit(
+        ctx: Context<FleetRefit>,
+        x: u16,
+        y: u16,
+        squadron: u8,
+        template: fleet::ShipTemplate,
+    ) -> Result<()> {
+        instructions::fleet_refit(ctx, x, y, squadron, template)
+    }
+    /// Move some ships of the fleet at (x, y) into a new fleet at the adjacent (to_x, to_y)
+    pub fn fleet_split(
+        ctx: Context<FleetSplit>,
+        x: u16,
+        y: u16,
+        to_x: u16,
+        to_y: u16,
+        amounts: [u16; fleet::SQUADRONS_IN_FLEET],
+    ) -> Result<()> {
+        instructions::fleet_split(ctx, x, y, to_x, to_y, amounts)
+    }
+    /// Fold the fleet at the adjacent (from_x, from_y) into the fleet at (x, y)
+    pub fn fleet_merge(
+        ctx: Context<FleetMerge>,
+        x: u16,
+        y: u16,
+        from_x: u16,
+        from_y: u16,
+    ) -> Result<()> {
+        instructions::fleet_merge(ctx, x, y, from_x, from_y)
+    }
+    /// Disband a fleet parked at an owned planet with a Shipyard, part of its value is refunded
+    pub fn fleet_disband(ctx: Context<FleetDisband>, x: u16, y: u16) -> Result<()> {
+        instructions::fleet_disband(ctx, x, y)
+    }
+    /// Salvage the debris field at or next to the fleet into the owner's cache, limited by
+    /// the fleet's hauling bays
+    pub fn fleet_salvage(
+        ctx: Context<FleetSalvage>,
+        x: u16,
+        y: u16,
+        debris_x: u16,
+        debris_y: u16,
+    ) -> Result<()> {
+        instructions::fleet_salvage(ctx, x, y, debris_x, debris_y)
+    }
+    /// Attack the fleet or the planet at the adjacent (target_x, target_y), planets are
+    /// captured with `planet_invade`. A beaten defender falls back to (retreat_x, retreat_y),
+    /// or to the first free position around the target in the remaining accounts when it is
+    /// blocked, see `fleet::retreat_candidates`. The winner can `advance` into the freed
+    /// position, or `bombard` a building of the defender's planet there.
+    #[allow(clippy::too_many_arguments)]
     pub fn fleet_attack(
         ctx: Context<FleetAttack>,
         x: u16,
         y: u16,
         target_x: u16,
         target_y: u16,
+        retreat_x: u16,
+        retreat_y: u16,
+        advance: bool,
+        bombard: Option<BuildingType>,
     ) -> Result<()> {
-        instructions::fleet_attack(ctx, x, y, target_x, target_y)
+        instructions::fleet_attack(
+            ctx, x, y, target_x, target_y, retreat_x, retreat_y, advance, bombard,
+        )
+    }
+    /// Close the attacker's battle report `id` once it's no longer needed, refunding its rent
+    pub fn battle_report_close(ctx: Context<BattleReportClose>, id: u64) -> Result<()> {
+        instructions::battle_report_close(ctx, id)
     }
 
     ///
@@ -57,6 +57,7 @@ pub trait MarketPoolAccount<'info> {
         is_resource: bool,
         token_program: &Program<'info, Token>,
     ) -> Result<()>;
+    #[allow(clippy::too_many_arguments)]
     fn mint_to_pool(
         &mut self,
         mint: &Account<'info, Mint>,
@@ -84,7 +85,7 @@ pub trait MarketPoolAccount<'info> {
         resource_authority_data: (&Account<'info, ResourceAuthority>, u8),
         is_resource: bool,
         token_program: &Program<'info, Token>,
-    ) -> Result<()>;
+    ) -> Result<u64>;
 }
 
 impl<'info> MarketPoolAccount<'info> for Account<'info, MarketPool> {
@@ -228,7 +229,8 @@ impl<'info> MarketPoolAccount<'info> for Account<'info, MarketPool> {
     /// based on the user's proposed amount of asset to pay, using the
     /// constant-product algorithm `r = f(p)`
     ///
-    /// Once calculated, it will process both transfers
+    /// Once calculated, it will process both transfers and return the amount
+    /// received
     fn process_swap(
         &mut self,
         receive: (
@@ -246,7 +248,7 @@ impl<'info> MarketPoolAccount<'info> for Account<'info, MarketPool> {
         resource_authority_data: (&Account<'info, ResourceAuthority>, u8),
         is_resource: bool,
         token_program: &Program<'info, Token>,
-    ) -> Result<()> {
+    ) -> Result<u64> {
         // (From, To)
         let (receive_mint, pool_recieve, payer_recieve) = receive;
         self.check_asset_key(&receive_mint.key())?;
@@ -281,7 +283,7 @@ impl<'info> MarketPoolAccount<'info> for Account<'info, MarketPool> {
                 self,
                 token_program,
             )?;
-            Ok(())
+            Ok(receive_amount)
         }
     }
 }
@@ -374,7 +376,7 @@ fn process_new_minting<'info>(
 /// receiving asset that can be returned in exchange for the amount of the paid
 /// asset offered
 ///
-/// ```
+/// ```text
 /// K = a * b * c * d * P * R
 /// K = a * b * c * d * (P + p) * (R - r)
 ///
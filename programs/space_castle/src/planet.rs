@@ -1,7 +1,34 @@
-use crate::building::Building;
+use crate::{
+    balance::BalanceValues,
+    building::{Building, BuildingErrorCode, BuildingType},
+    mint_decimals,
+    resource::Resources,
+    utilities::{calculate_upgrade_cost, convert_from_float},
+};
 use anchor_lang::prelude::*;
 use solana_program::blake3::hashv;
 
+/// Default slots in one production tick (~1 minute at 400ms per slot), see `BalanceValues`
+pub const PRODUCTION_TICK_SLOTS: u64 = 150;
+/// Unharvested production stops piling up after a day's worth of slots
+pub const MAX_PRODUCTION_SLOTS: u64 = PRODUCTION_TICK_SLOTS * 60 * 24;
+/// Resources produced per tick by every planet, even without an industry
+pub const BASE_PRODUCTION: u64 = 1;
+/// Additional resources produced per tick for each level of the industry building
+pub const PRODUCTION_PER_LEVEL: u64 = 2;
+/// IGT produced per tick
+pub const IGT_PRODUCTION: u64 = 1;
+/// Landing pods that are used up when colonizing a planet
+pub const COLONIZATION_LANDING_PODS: u16 = 5;
+/// Default colonization cost for the first colony: [metal, crystal, chemical, fuel]
+pub const COLONIZATION_BASE_COST: [f32; 4] = [100.0, 100.0, 100.0, 100.0];
+/// Default IGT cost for the first colony
+pub const COLONIZATION_BASE_COST_IGT: f32 = 10.0;
+/// Each next colony costs this much more than the previous one by default
+pub const COLONIZATION_COST_FACTOR: f32 = 1.5;
+/// Richness of a planet is a u16, so production is scaled from 1x to 2x by it
+const RICHNESS_SCALE: u128 = u16::MAX as u128 + 1;
+
 #[account]
 #[derive(InitSpace)]
 pub struct PlanetInfo {
@@ -14,8 +41,26 @@ pub struct PlanetInfo {
 }
 
 impl PlanetInfo {
+    /// Claims the planet for a new owner and stores its procedural resource profile, so
+    /// clients can read it straight from the account
+    pub fn settle(&mut self, owner: Pubkey, x: u16, y: u16) {
+        let [metal, crystal, chemical, fuel] = get_planet_resources(x, y);
+        self.metal = metal;
+        self.crystal = crystal;
+        self.chemical = chemical;
+        self.fuel = fuel;
+        self.owner = Some(owner);
+        self.miner = None;
+    }
+    /// Planet resources in the same order as `get_planet_resources`
+    ///
+    /// * [`metal`, `crystal`, `chemical`, `fuel`]
+    ///
+    pub fn resources(&self) -> [u16; 4] {
+        [self.metal, self.crystal, self.chemical, self.fuel]
+    }
     pub fn planet_affinity(&self) -> u8 {
-        get_planet_affinity([self.metal, self.crystal, self.chemical, self.fuel])
+        get_planet_affinity(self.resources())
     }
     /// Checks if the planet has a specific owner
     pub fn is_owned_by(&self, owner: &Pubkey) -> bool {
@@ -33,6 +78,202 @@ pub struct PlanetHolding {
     pub last_harvest: u64,
     #[max_len(6)]
     pub buildings: [Building; 6],
+    /// Production taken by raiders since the last harvest
+    pub plundered: Resources,
+}
+
+impl PlanetHolding {
+    /// Reads a holding from an account that may not exist, e.g. a position without a
+    /// planet of the expected owner
+    pub fn try_from_optional(account: &AccountInfo) -> Result<Option<PlanetHolding>> {
+        if account.owner != &crate::ID || account.data_is_empty() {
+            return Ok(None);
+        }
+        PlanetHolding::try_deserialize(&mut &account.data.borrow()[..]).map(Some)
+    }
+
+    /// Writes a holding read with `try_from_optional` back into its account
+    pub fn save_to(&self, account: &AccountInfo) -> Result<()> {
+        self.try_serialize(&mut &mut account.try_borrow_mut_data()?[..])
+    }
+
+    /// Bombards a building on the planet, see `Building::bombard`
+    ///
+    /// # Returns
+    ///
+    /// * Number of levels the building lost
+    ///
+    pub fn bombard(
+        &mut self,
+        building_type: BuildingType,
+        explosive: u64,
+        balance: &BalanceValues,
+    ) -> Result<u8> {
+        self.buildings
+            .iter_mut()
+            .find(|b| b.building_type.eq(&building_type) && building_type != BuildingType::None)
+            .map(|b| b.bombard(explosive, balance))
+            .ok_or_else(|| BuildingErrorCode::BuildingNotPresent.into())
+    }
+
+    /// Production since the last harvest that wasn't plundered yet
+    pub fn unclaimed_production(
+        &self,
+        planet_resources: [u16; 4],
+        current_slot: u64,
+        balance: &BalanceValues,
+    ) -> Resources {
+        self.calculate_production(planet_resources, current_slot, balance)
+            .sub(self.plundered.clone())
+    }
+
+    /// Takes as much of the unclaimed production as fits into the cargo
+    ///
+    /// # Returns
+    ///
+    /// * Resources taken from the planet
+    ///
+    pub fn plunder(
+        &mut self,
+        planet_resources: [u16; 4],
+        current_slot: u64,
+        cargo_capacity: u64,
+        balance: &BalanceValues,
+    ) -> Resources {
+        let loot = self
+            .unclaimed_production(planet_resources, current_slot, balance)
+            .load_cargo(cargo_capacity);
+        self.plundered = self.plundered.sum(loot.clone());
+        loot
+    }
+
+    /// Level of a building on the planet, 0 if it isn't built
+    pub fn building_level(&self, building_type: BuildingType) -> u8 {
+        self.buildings
+            .iter()
+            .find(|b| b.building_type.eq(&building_type))
+            .map_or(0, |b| b.level)
+    }
+
+    /// Slots of whole production ticks since the last harvest, unharvested production stops
+    /// piling up after `max_production_slots`
+    pub fn production_slots(&self, current_slot: u64, balance: &BalanceValues) -> u64 {
+        let elapsed_slots = current_slot
+            .saturating_sub(self.last_harvest)
+            .min(balance.max_production_slots);
+        elapsed_slots - elapsed_slots % balance.production_tick_slots
+    }
+
+    /// Slot a harvest at `current_slot` pays production up to, the unfinished tick is left
+    /// for the next harvest
+    pub fn harvested_until(&self, current_slot: u64, balance: &BalanceValues) -> u64 {
+        self.last_harvest
+            .max(current_slot.saturating_sub(balance.max_production_slots))
+            .saturating_add(self.production_slots(current_slot, balance))
+    }
+
+    /// Calculates resources the planet produced since its last harvest
+    ///
+    /// # Arguments
+    ///
+    /// * `planet_resources` - [metal, crystal, chemical, fuel] as given by `get_planet_resources`
+    /// * `current_slot` - slot of the harvest
+    /// * `balance` - production rates
+    ///
+    pub fn calculate_production(
+        &self,
+        planet_resources: [u16; 4],
+        current_slot: u64,
+        balance: &BalanceValues,
+    ) -> Resources {
+        let elapsed_slots = self.production_slots(current_slot, balance);
+        let [metal, crystal, chemical, fuel] = planet_resources;
+        Resources {
+            igt: (elapsed_slots as u128 * balance.igt_production as u128
+                / balance.production_tick_slots as u128)
+                .min(u64::MAX as u128) as u64,
+            metal: resource_production(
+                metal,
+                self.building_level(BuildingType::MetalIndustry),
+                elapsed_slots,
+                balance,
+            ),
+            crystal: resource_production(
+                crystal,
+                self.building_level(BuildingType::CrystalLabs),
+                elapsed_slots,
+                balance,
+            ),
+            chemical: resource_production(
+                chemical,
+                self.building_level(BuildingType::ChemicalRefinery),
+                elapsed_slots,
+                balance,
+            ),
+            fuel: resource_production(
+                fuel,
+                self.building_level(BuildingType::FuelExtractors),
+                elapsed_slots,
+                balance,
+            ),
+        }
+    }
+}
+
+/// Amount of a resource produced over a number of slots
+///
+/// # Arguments
+///
+/// * `richness` - planet's richness in this resource
+/// * `building_level` - level of the industry producing this resource
+/// * `elapsed_slots` - slots of production
+/// * `balance` - production rates
+///
+fn resource_production(
+    richness: u16,
+    building_level: u8,
+    elapsed_slots: u64,
+    balance: &BalanceValues,
+) -> u64 {
+    let per_tick = balance.base_production as u128
+        + balance.production_per_level as u128 * building_level as u128;
+    let amount = elapsed_slots as u128 * per_tick * (RICHNESS_SCALE + richness as u128)
+        / (RICHNESS_SCALE * balance.production_tick_slots as u128);
+    amount.min(u64::MAX as u128) as u64
+}
+
+/// Calculates the cost of settling a new colony
+///
+/// # Arguments
+///
+/// * `settled_planets` - number of planets the player already owns
+/// * `balance` - colonization costs
+///
+pub fn colonization_cost(settled_planets: u8, balance: &BalanceValues) -> Resources {
+    let [metal, crystal, chemical, fuel] = balance.colonization_base_costs;
+    let factor = balance.colonization_cost_factor;
+    Resources {
+        igt: convert_from_float(
+            calculate_upgrade_cost(balance.colonization_base_cost_igt, factor, settled_planets),
+            mint_decimals::IGT,
+        ),
+        metal: convert_from_float(
+            calculate_upgrade_cost(metal, factor, settled_planets),
+            mint_decimals::METAL,
+        ),
+        crystal: convert_from_float(
+            calculate_upgrade_cost(crystal, factor, settled_planets),
+            mint_decimals::CRYSTAL,
+        ),
+        chemical: convert_from_float(
+            calculate_upgrade_cost(chemical, factor, settled_planets),
+            mint_decimals::CHEMICAL,
+        ),
+        fuel: convert_from_float(
+            calculate_upgrade_cost(fuel, factor, settled_planets),
+            mint_decimals::FUEL,
+        ),
+    }
 }
 
 pub fn are_planet_coordinates_valid(x: u16, y: u16) -> bool {
@@ -104,4 +345,73 @@ pub enum PlanetErrorCode {
     NoPlanetAtCoordinates,
     #[msg("No authority over this planet)")]
     NoAuthority,
+    #[msg("Planet has not produced anything since the last harvest")]
+    NothingToHarvest,
+    #[msg("Planet is not the player's planetary capital")]
+    NoPlanetaryCapital,
+    #[msg("Planetary capital level is too low for another colony")]
+    ColonyLimitReached,
+    #[msg("Planets with a planetary capital cannot be captured")]
+    CapitalCannotBeCaptured,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Planet without an industry, it produces 1 of everything per tick
+    fn holding(plundered: Resources) -> PlanetHolding {
+        PlanetHolding {
+            last_harvest: 0,
+            buildings: [Building::default(); 6],
+            plundered,
+        }
+    }
+
+    fn resources(igt: u64, amount: u64) -> Resources {
+        Resources {
+            igt,
+            metal: amount,
+            crystal: amount,
+            chemical: amount,
+            fuel: amount,
+        }
+    }
+
+    #[test]
+    fn unclaimed_production_leaves_out_the_plundered_part() {
+        let balance = BalanceValues::default();
+        let slot = 10 * balance.production_tick_slots;
+        let holding = holding(Resources {
+            metal: 4,
+            ..Resources::default()
+        });
+        let unclaimed = holding.unclaimed_production([0; 4], slot, &balance);
+        assert_eq!(
+            unclaimed,
+            Resources {
+                metal: 6,
+                ..resources(10, 10)
+            }
+        );
+    }
+
+    #[test]
+    fn plunder_takes_what_fits_and_only_once() {
+        let balance = BalanceValues::default();
+        let slot = 10 * balance.production_tick_slots;
+        let mut holding = holding(Resources::default());
+        assert_eq!(holding.plunder([0; 4], slot, 20, &balance), resources(0, 5));
+        assert_eq!(holding.plundered, resources(0, 5));
+        assert_eq!(
+            holding.plunder([0; 4], slot, 100, &balance),
+            resources(0, 5)
+        );
+        assert!(holding.plunder([0; 4], slot, 100, &balance).is_empty());
+        // Harvesting owner still gets the IGT
+        assert_eq!(
+            holding.unclaimed_production([0; 4], slot, &balance),
+            resources(10, 0)
+        );
+    }
 }
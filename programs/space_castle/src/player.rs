@@ -6,6 +6,28 @@ pub struct Player {
     #[max_len(32)]
     pub name: String,
     pub settled_planets: u8,
+    pub battles_won: u32,
+    pub battles_lost: u32,
+    /// Battle reports of attacks by the player, also the id of the next one
+    pub battle_reports: u64,
+}
+
+impl Player {
+    /// Takes the id for the report of a battle the player starts
+    pub fn open_battle_report(&mut self) -> u64 {
+        let id = self.battle_reports;
+        self.battle_reports = self.battle_reports.saturating_add(1);
+        id
+    }
+
+    /// Counts a battle the player fought on either side
+    pub fn record_battle(&mut self, won: bool) {
+        if won {
+            self.battles_won = self.battles_won.saturating_add(1);
+        } else {
+            self.battles_lost = self.battles_lost.saturating_add(1);
+        }
+    }
 }
 
 #[error_code]
@@ -2,8 +2,8 @@ use anchor_lang::prelude::*;
 use anchor_spl::token::{self, Burn, Mint, Token, TokenAccount};
 
 use crate::{
-    process_mint_chemical, process_mint_crystal, process_mint_fuel, process_mint_igt,
-    process_mint_metal, seeds,
+    mint_decimals, process_mint_chemical, process_mint_crystal, process_mint_fuel,
+    process_mint_igt, process_mint_metal, seeds,
 };
 
 #[account]
@@ -12,7 +12,8 @@ pub struct PlayerCache {
     pub resources: Resources,
 }
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default, InitSpace)]
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default, InitSpace, PartialEq, Debug)]
+#[cfg_attr(feature = "simulator", derive(serde::Serialize, serde::Deserialize))]
 pub struct Resources {
     pub igt: u64,
     pub metal: u64,
@@ -31,6 +32,21 @@ impl Resources {
             fuel: 0,
         }
     }
+    pub fn is_empty(&self) -> bool {
+        self.igt == 0
+            && self.metal == 0
+            && self.crystal == 0
+            && self.chemical == 0
+            && self.fuel == 0
+    }
+    /// Checks if there is at least as much of every resource as in `costs`
+    pub fn covers(&self, costs: &Resources) -> bool {
+        self.igt >= costs.igt
+            && self.metal >= costs.metal
+            && self.crystal >= costs.crystal
+            && self.chemical >= costs.chemical
+            && self.fuel >= costs.fuel
+    }
     pub fn by_key(&self, key: &str) -> u64 {
         match key {
             "metal" => self.metal,
@@ -75,6 +91,50 @@ impl Resources {
             fuel: self.fuel.saturating_div(factor),
         }
     }
+    /// Converts amounts in the smallest token units into whole tokens, which is what
+    /// `PlayerCache` keeps and `mint` expects
+    pub fn to_whole_units(&self) -> Resources {
+        Resources {
+            igt: self.igt / 10u64.pow(mint_decimals::IGT as u32),
+            metal: self.metal / 10u64.pow(mint_decimals::METAL as u32),
+            crystal: self.crystal / 10u64.pow(mint_decimals::CRYSTAL as u32),
+            chemical: self.chemical / 10u64.pow(mint_decimals::CHEMICAL as u32),
+            fuel: self.fuel / 10u64.pow(mint_decimals::FUEL as u32),
+        }
+    }
+    /// Part of the resources, IGT excluded, that fits into cargo of the given capacity.
+    /// Every resource is loaded in proportion to its amount.
+    pub fn load_cargo(&self, capacity: u64) -> Resources {
+        let total =
+            self.metal as u128 + self.crystal as u128 + self.chemical as u128 + self.fuel as u128;
+        if total <= capacity as u128 {
+            return Resources {
+                igt: 0,
+                ..self.clone()
+            };
+        }
+        let load = |amount: u64| (amount as u128 * capacity as u128 / total) as u64;
+        Resources {
+            igt: 0,
+            metal: load(self.metal),
+            crystal: load(self.crystal),
+            chemical: load(self.chemical),
+            fuel: load(self.fuel),
+        }
+    }
+    /// Takes `percent` of every amount without overflowing on the way
+    pub fn percent(&self, percent: u64) -> Resources {
+        let part = |amount: u64| {
+            (amount as u128 * percent as u128 / 100).min(u64::MAX as u128) as u64
+        };
+        Resources {
+            igt: part(self.igt),
+            metal: part(self.metal),
+            crystal: part(self.crystal),
+            chemical: part(self.chemical),
+            fuel: part(self.fuel),
+        }
+    }
     pub fn mul(&self, factor: u64) -> Resources {
         Resources {
             igt: self.igt.saturating_mul(factor),
@@ -84,6 +144,7 @@ impl Resources {
             fuel: self.fuel.saturating_mul(factor),
         }
     }
+    #[allow(clippy::type_complexity)]
     pub fn mint<'info>(
         &self,
         token_program: &Program<'info, Token>,
@@ -237,3 +298,41 @@ pub fn process_burn_resource<'info>(
     let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds);
     token::burn(cpi_ctx, amount)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn resources(igt: u64, metal: u64, crystal: u64) -> Resources {
+        Resources {
+            igt,
+            metal,
+            crystal,
+            chemical: 0,
+            fuel: 0,
+        }
+    }
+
+    #[test]
+    fn percent_of_large_amounts_does_not_saturate() {
+        assert_eq!(
+            resources(u64::MAX, 200, 0).percent(30),
+            resources((u64::MAX as u128 * 30 / 100) as u64, 60, 0)
+        );
+    }
+
+    #[test]
+    fn cargo_takes_everything_that_fits_but_igt() {
+        assert_eq!(resources(5, 10, 10).load_cargo(20), resources(0, 10, 10));
+    }
+
+    #[test]
+    fn cargo_is_loaded_in_proportion() {
+        assert_eq!(resources(5, 30, 10).load_cargo(20), resources(0, 15, 5));
+    }
+
+    #[test]
+    fn cargo_without_capacity_stays_empty() {
+        assert!(resources(5, 30, 10).load_cargo(0).is_empty());
+    }
+}
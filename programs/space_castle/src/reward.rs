@@ -0,0 +1,116 @@
+use crate::{
+    battle::{BattleResult, BattleSide},
+    resource::{PlayerCache, Resources},
+};
+
+/// Winner salvages this part (1 / n) of its own losses
+pub const OWN_LOSSES_SALVAGE_DIVISOR: u64 = 3;
+/// Winner plunders this part (1 / n) of the loser's losses
+pub const ENEMY_LOSSES_SALVAGE_DIVISOR: u64 = 5;
+
+/// Resources each side of a battle is credited with, in the smallest token units
+#[derive(Debug, Default, PartialEq)]
+pub struct BattleRewards {
+    pub attacker: Resources,
+    pub defender: Resources,
+}
+
+impl BattleRewards {
+    /// The winner salvages a part of both sides' losses, the loser gets nothing
+    pub fn from_result(result: &BattleResult) -> Self {
+        match result.winner {
+            BattleSide::Attacker => BattleRewards {
+                attacker: salvage(&result.att_losses, &result.def_losses),
+                defender: Resources::default(),
+            },
+            BattleSide::Defender => BattleRewards {
+                attacker: Resources::default(),
+                defender: salvage(&result.def_losses, &result.att_losses),
+            },
+        }
+    }
+
+    /// Resources credited to the winner
+    pub fn winner_reward(&self, winner: BattleSide) -> Resources {
+        match winner {
+            BattleSide::Attacker => self.attacker.clone(),
+            BattleSide::Defender => self.defender.clone(),
+        }
+    }
+
+    /// Adds the rewards to the caches of both sides, caches keep whole tokens
+    pub fn credit(&self, attacker_cache: &mut PlayerCache, defender_cache: &mut PlayerCache) {
+        attacker_cache.resources = attacker_cache.resources.sum(self.attacker.to_whole_units());
+        defender_cache.resources = defender_cache.resources.sum(self.defender.to_whole_units());
+    }
+}
+
+fn salvage(own_losses: &Resources, enemy_losses: &Resources) -> Resources {
+    own_losses
+        .div(OWN_LOSSES_SALVAGE_DIVISOR)
+        .sum(enemy_losses.div(ENEMY_LOSSES_SALVAGE_DIVISOR))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::battle::MAX_ROUNDS;
+
+    fn resources(amount: u64) -> Resources {
+        Resources {
+            igt: amount,
+            metal: amount,
+            crystal: amount,
+            chemical: amount,
+            fuel: amount,
+        }
+    }
+
+    fn result(winner: BattleSide, att_losses: u64, def_losses: u64) -> BattleResult {
+        BattleResult {
+            winner,
+            rounds: [None; MAX_ROUNDS],
+            att_losses: resources(att_losses),
+            def_losses: resources(def_losses),
+        }
+    }
+
+    #[test]
+    fn attacker_win_pays_attacker_only() {
+        let rewards = BattleRewards::from_result(&result(BattleSide::Attacker, 300, 500));
+        assert_eq!(rewards.attacker, resources(100 + 100));
+        assert_eq!(rewards.defender, Resources::default());
+        assert_eq!(rewards.winner_reward(BattleSide::Attacker), resources(200));
+    }
+
+    #[test]
+    fn defender_win_pays_defender_only() {
+        let rewards = BattleRewards::from_result(&result(BattleSide::Defender, 500, 300));
+        assert_eq!(rewards.attacker, Resources::default());
+        assert_eq!(rewards.defender, resources(100 + 100));
+        assert_eq!(rewards.winner_reward(BattleSide::Defender), resources(200));
+    }
+
+    #[test]
+    fn battle_without_losses_pays_nothing() {
+        for winner in [BattleSide::Attacker, BattleSide::Defender] {
+            let rewards = BattleRewards::from_result(&result(winner, 0, 0));
+            assert_eq!(rewards, BattleRewards::default());
+        }
+    }
+
+    #[test]
+    fn credit_converts_to_whole_units() {
+        let whole = 10u64.pow(crate::mint_decimals::METAL as u32);
+        let rewards = BattleRewards::from_result(&result(BattleSide::Defender, 10 * whole, 0));
+        let mut attacker_cache = PlayerCache {
+            resources: Resources::default(),
+        };
+        let mut defender_cache = PlayerCache {
+            resources: resources(1),
+        };
+        rewards.credit(&mut attacker_cache, &mut defender_cache);
+        assert_eq!(attacker_cache.resources, Resources::default());
+        assert_eq!(defender_cache.resources.metal, 1 + 2);
+    }
+}
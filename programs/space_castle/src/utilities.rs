@@ -1,5 +1,8 @@
 use std::ops::{Div, Mul};
 
+/// Fixed point precision growth factors are applied with in integer math
+const GROWTH_FACTOR_SCALE: u128 = 1_000_000;
+
 /// Converts a `u64` value - in this case the balance of a token account - into
 /// an `f32` by using the `decimals` value of its associated mint to get the
 /// nominal quantity of a mint stored in that token account
@@ -17,10 +20,45 @@ pub fn convert_to_float(value: u64, decimals: u8) -> f32 {
 /// For example, if `r` is calculated to be 10.5, the real amount of the asset
 /// to be received by the user is 10,500
 pub fn convert_from_float(value: f32, decimals: u8) -> u64 {
-    value.mul(f32::powf(10.0, decimals as f32)) as u64
+    value.mul(10u64.pow(decimals as u32) as f32) as u64
 }
 
 /// Calculates upgrade cost with a growth factor so the upgrading costs a bit more each level
 pub fn calculate_upgrade_cost(base_cost: f32, growth_factor: f32, level: u8) -> f32 {
     base_cost * (growth_factor.powf((level.saturating_sub(1)) as f32))
 }
+
+/// Same as `calculate_upgrade_cost`, but returns the smallest units of a mint with `decimals`
+/// and grows the cost level by level in fixed point integer math. Quotes that battle losses
+/// are valued with don't depend on how a platform rounds `powf` this way.
+pub fn calculate_upgrade_cost_units(
+    base_cost: f32,
+    growth_factor: f32,
+    level: u8,
+    decimals: u8,
+) -> u64 {
+    let factor = (growth_factor as f64 * GROWTH_FACTOR_SCALE as f64) as u128;
+    let mut cost = convert_from_float(base_cost, decimals) as u128;
+    for _ in 1..level {
+        cost = (cost.saturating_mul(factor) / GROWTH_FACTOR_SCALE).min(u64::MAX as u128);
+    }
+    cost as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn upgrade_cost_units_grow_by_the_factor_each_level() {
+        assert_eq!(calculate_upgrade_cost_units(10.0, 1.5, 1, 8), 1_000_000_000);
+        assert_eq!(calculate_upgrade_cost_units(10.0, 1.5, 3, 8), 2_250_000_000);
+        // Level 0 is priced like the first level, as in `calculate_upgrade_cost`
+        assert_eq!(calculate_upgrade_cost_units(10.0, 1.5, 0, 8), 1_000_000_000);
+    }
+
+    #[test]
+    fn upgrade_cost_units_saturate() {
+        assert_eq!(calculate_upgrade_cost_units(10.0, 1e30, 255, 8), u64::MAX);
+    }
+}
@@ -89,6 +89,56 @@ describe('[Test]: 💰 Mints', () => {
     playerWallet = (await usePlayer(1, program.programId)).keypair
   })
 
+  it('Game is initialized by the program upgrade authority', async () => {
+    const [programData] = PublicKey.findProgramAddressSync(
+      [program.programId.toBuffer()],
+      new PublicKey('BPFLoaderUpgradeab1e11111111111111111111111'),
+    )
+    await program.methods
+      .initializeGame(provider.wallet.publicKey)
+      .accounts({
+        signer: provider.wallet.publicKey,
+      })
+      .accountsPartial({ programData })
+      .rpc()
+  })
+
+  it('Balance config is created with default values', async () => {
+    await program.methods
+      .balanceConfigInitialize()
+      .accounts({
+        admin: provider.wallet.publicKey,
+      })
+      .rpc()
+    const [balanceConfigKey] = PublicKey.findProgramAddressSync(
+      [Buffer.from('balance_config')],
+      program.programId,
+    )
+    const balanceConfig =
+      await program.account.balanceConfig.fetch(balanceConfigKey)
+    assert.equal(balanceConfig.version, 1)
+  })
+
+  it('Balance values are validated on update', async () => {
+    const [balanceConfigKey] = PublicKey.findProgramAddressSync(
+      [Buffer.from('balance_config')],
+      program.programId,
+    )
+    const { values } =
+      await program.account.balanceConfig.fetch(balanceConfigKey)
+    try {
+      await program.methods
+        .balanceConfigUpdate({ ...values, productionTickSlots: new anchor.BN(0) })
+        .accounts({
+          admin: provider.wallet.publicKey,
+        })
+        .rpc()
+      assert.fail('Balance config with zero tick slots should be rejected')
+    } catch (e) {
+      assert.include(e.toString(), 'InvalidValues')
+    }
+  })
+
   it('Intergalactic Tender (IGT) + metadata - main currency', async () => {
     await program.methods
       .mintInitIgt()
@@ -151,4 +201,19 @@ describe('[Test]: 💰 Mints', () => {
         return assert.fail(e)
       })
   })
+  it('Only the game admin can mint tokens', async () => {
+    try {
+      await program.methods
+        .mintMetal(new anchor.BN(1000))
+        .accounts({
+          payer: playerWallet.publicKey,
+          admin: playerWallet.publicKey,
+        })
+        .signers([playerWallet])
+        .rpc()
+      return assert.fail('Minted tokens without being the admin')
+    } catch (e) {
+      return assert.ok('Ok')
+    }
+  })
 })
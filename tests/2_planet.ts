@@ -10,6 +10,9 @@ import {
   getPlayerCache,
   usePlayer,
 } from './utils/player'
+import { PRODUCTION_TICK_SLOTS, waitForSlots } from './utils/provider'
+import { number_to_bytes_buffer } from './utils/formatting'
+import { getHoldings } from './utils/planet'
 
 describe('[Test]: 🪐 Planet', () => {
   const provider = anchor.AnchorProvider.env()
@@ -18,6 +21,7 @@ describe('[Test]: 🪐 Planet', () => {
   let playerWallet: PlayerInfo
   let secondPlayerWallet: PlayerInfo
   let playerBalances: PlayerBalances
+  let lastHarvest: number
 
   before('Prepare wallet and player accounts', async () => {
     playerWallet = await usePlayer(1, program.programId)
@@ -43,6 +47,25 @@ describe('[Test]: 🪐 Planet', () => {
       })
   })
 
+  it('Claimed planet stores its resource profile', async () => {
+    const [planetInfoPDA] = anchor.web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from('planet_info'),
+        number_to_bytes_buffer(1),
+        number_to_bytes_buffer(3),
+      ],
+      program.programId,
+    )
+    const planetInfo = await program.account.planetInfo.fetch(planetInfoPDA)
+    return planetInfo.metal +
+      planetInfo.crystal +
+      planetInfo.chemical +
+      planetInfo.fuel >
+      0
+      ? assert.ok('Planet has its resources stored')
+      : assert.fail('Planet resources were not stored')
+  })
+
   it('First free planet be claimed only once', async () => {
     try {
       await program.methods
@@ -58,6 +81,21 @@ describe('[Test]: 🪐 Planet', () => {
     }
   })
 
+  it("Can't colonize a planet without a fleet carrying landing pods", async () => {
+    try {
+      await program.methods
+        .planetColonize(2, 20, 1, 3)
+        .accounts({
+          signer: playerWallet.keypair.publicKey,
+        })
+        .signers([playerWallet.keypair])
+        .rpc()
+      return assert.fail('Colonized a planet without landing pods')
+    } catch {
+      return assert.ok("Couldn't colonize without landing pods")
+    }
+  })
+
   it("Can't claim a planet where there is no planet", async () => {
     try {
       await program.methods
@@ -161,6 +199,15 @@ describe('[Test]: 🪐 Planet', () => {
   })
 
   it('Planet harvesting, this grants player IGT and resources', async () => {
+    // Production is time based, let the planet produce for a while
+    await waitForSlots(provider.connection, PRODUCTION_TICK_SLOTS * 2)
+    const holding = await getHoldings(
+      1,
+      3,
+      playerWallet.keypair.publicKey,
+      program,
+    )
+    lastHarvest = holding.lastHarvest.toNumber()
     await program.methods
       .planetHarvest(1, 3)
       .accounts({ signer: playerWallet.keypair.publicKey })
@@ -188,6 +235,31 @@ describe('[Test]: 🪐 Planet', () => {
     }
   })
 
+  it('Harvest keeps the unfinished production tick for later', async () => {
+    const holding = await getHoldings(
+      1,
+      3,
+      playerWallet.keypair.publicKey,
+      program,
+    )
+    const harvestedSlots = holding.lastHarvest.toNumber() - lastHarvest
+    assert.isAtLeast(harvestedSlots, PRODUCTION_TICK_SLOTS * 2)
+    assert.equal(harvestedSlots % PRODUCTION_TICK_SLOTS, 0)
+  })
+
+  it('Planet cannot be harvested again right away', async () => {
+    try {
+      await program.methods
+        .planetHarvest(1, 3)
+        .accounts({ signer: playerWallet.keypair.publicKey })
+        .signers([playerWallet.keypair])
+        .rpc()
+      return assert.fail('Harvested without any production')
+    } catch (e) {
+      return assert.ok('Ok')
+    }
+  })
+
   it('Planet can only be harvested by its owner', async () => {
     try {
       await program.methods
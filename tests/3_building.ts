@@ -156,4 +156,31 @@ describe('[Test]: 🏰 Buildings', () => {
       return assert.fail('Looks like no resources were used up.')
     }
   })
+
+  it('Defense buildings can be built on the planet', async () => {
+    await program.methods
+      .planetBuildingNew(1, 3, { orbitalBattery: {} })
+      .accounts({
+        signer: playerWallet.publicKey,
+      })
+      .signers([playerWallet])
+      .rpc()
+    const holding = await getHoldings(1, 3, playerWallet.publicKey, program)
+    assert.equal(getBuilding(holding, 'orbitalBattery')?.level, 1)
+  })
+
+  it('Only buildings damaged by bombardment can be repaired', async () => {
+    try {
+      await program.methods
+        .planetBuildingRepair(1, 3, { orbitalBattery: {} })
+        .accounts({
+          signer: playerWallet.publicKey,
+        })
+        .signers([playerWallet])
+        .rpc()
+      return assert.fail('Repaired a building without damage')
+    } catch (e) {
+      assert.include(e.toString(), 'NothingToRepair')
+    }
+  })
 })
@@ -95,6 +95,7 @@ describe('[Test] 💱 Market pool - a global automated market and market maker',
         .accounts({
           mint: mint[0],
           payer: payer.publicKey,
+          admin: payer.publicKey,
         })
         .signers([payer])
         .rpc()
@@ -9,7 +9,16 @@ import {
   usePlayer,
 } from './utils/player'
 import { assert } from 'chai'
-import { createSimpleFleetTemplate, fleetKey } from './utils/fleet'
+import {
+  constructFleet,
+  constructShip,
+  createSimpleFleetTemplate,
+  fleetKey,
+  nextBattleReportKey,
+  padShipModules,
+  shipDesignKey,
+  waitForArrival,
+} from './utils/fleet'
 
 describe('[Test]: 🚀 Fleet', () => {
   const program = anchor.workspace.SpaceCastle as Program<SpaceCastle>
@@ -47,6 +56,69 @@ describe('[Test]: 🚀 Fleet', () => {
         return assert.fail(e)
       })
   })
+  it('Fleet in transit cannot be moved again', async () => {
+    try {
+      await program.methods
+        .fleetMove(1, 3, 1, 4)
+        .accounts({
+          signer: playerWallet.keypair.publicKey,
+        })
+        .signers([playerWallet.keypair])
+        .rpc()
+      return assert.fail('Fleet was moved while in transit')
+    } catch (e) {
+      assert.include(e.toString(), 'FleetInTransit')
+    }
+  })
+  it('Fleet in transit cannot be attacked', async () => {
+    try {
+      await program.methods
+        .fleetAttack(2, 6, 1, 3, 1, 2, false, null)
+        .accounts({
+          signer: secondPlayerWallet.keypair.publicKey,
+        })
+        .accountsPartial({
+          defender: playerWallet.keypair.publicKey,
+          battleReport: await nextBattleReportKey(
+            secondPlayerWallet.keypair.publicKey,
+            program,
+          ),
+        })
+        .signers([secondPlayerWallet.keypair])
+        .rpc()
+      return assert.fail('Fleet was attacked while in transit')
+    } catch (e) {
+      assert.include(e.toString(), 'FleetInTransit')
+    }
+  })
+  it('Destination of a travelling fleet is kept free for it', async () => {
+    try {
+      await program.methods
+        .fleetMove(2, 6, 2, 3)
+        .accounts({
+          signer: secondPlayerWallet.keypair.publicKey,
+        })
+        .signers([secondPlayerWallet.keypair])
+        .rpc()
+      return assert.fail('Fleet moved to a reserved destination')
+    } catch (e) {
+      assert.include(e.toString(), 'IllegalMoveAlreadyOccupied')
+    }
+  })
+  it('Fleet arrives after travelling', async () => {
+    await waitForArrival(1, 3, program)
+    await program.methods
+      .fleetArrive(1, 3, 2, 3)
+      .accounts({
+        signer: secondPlayerWallet.keypair.publicKey,
+      })
+      .signers([secondPlayerWallet.keypair])
+      .rpc()
+    const fleet = await program.account.fleet.fetch(fleetKey(2, 3))
+    assert.ok(fleet.isPresent)
+    assert.ok(fleet.owner.equals(playerWallet.keypair.publicKey))
+    assert.isNull(fleet.transit)
+  })
   it('Moving a fleet costs resources', async () => {
     const prevBalances = { ...playerBalances }
     playerBalances = await getPlayerBalances(
@@ -93,6 +165,46 @@ describe('[Test]: 🚀 Fleet', () => {
     return assert.fail('Somehow fleet moved where another was present')
   })
 
+  it('Fleet cannot be created without enough resources', async () => {
+    try {
+      await program.methods
+        .fleetNew(
+          1,
+          3,
+          constructFleet([
+            [padShipModules([['haulingBay', 1]]), 60000, 'carrier'],
+          ]),
+        )
+        .accounts({
+          signer: playerWallet.keypair.publicKey,
+        })
+        .signers([playerWallet.keypair])
+        .rpc()
+      return assert.fail('Fleet was created without paying for it')
+    } catch (e) {
+      assert.include(e.toString(), 'NotEnoughResources')
+    }
+  })
+
+  it('Modules cannot be built above the level of their building', async () => {
+    try {
+      await program.methods
+        .fleetNew(
+          1,
+          3,
+          constructFleet([[padShipModules([['landingPods', 5]]), 1, 'carrier']]),
+        )
+        .accounts({
+          signer: playerWallet.keypair.publicKey,
+        })
+        .signers([playerWallet.keypair])
+        .rpc()
+      return assert.fail('Module was built above the Astral Navy HQ level')
+    } catch (e) {
+      assert.include(e.toString(), 'ModuleLevelTooHigh')
+    }
+  })
+
   it('Creating a fleet', async () => {
     await program.methods
       .fleetNew(1, 3, createSimpleFleetTemplate())
@@ -103,22 +215,222 @@ describe('[Test]: 🚀 Fleet', () => {
       .rpc()
   })
 
-  it('Fleet cannot be created if another fleet is on that planet', async () => {
+  it('Creating a fleet costs resources', async () => {
+    const prevBalances = { ...playerBalances }
+    playerBalances = await getPlayerBalances(
+      playerWallet.keypair,
+      program.programId,
+      provider,
+    )
+    const diff = balanceDiff(playerBalances, prevBalances)
+    return diff.metal >= 0 || diff.igt >= 0
+      ? assert.fail('Fleet was built for free')
+      : assert.ok('All ok')
+  })
+
+  it('Building at a shipyard with a parked fleet reinforces it', async () => {
+    await program.methods
+      .fleetNew(1, 3, createSimpleFleetTemplate())
+      .accounts({
+        signer: playerWallet.keypair.publicKey,
+      })
+      .signers([playerWallet.keypair])
+      .rpc()
+    const fleet = await program.account.fleet.fetch(fleetKey(1, 3))
+    const squadrons = fleet.squadrons.filter((s) => s !== null)
+    assert.equal(squadrons.length, 1)
+    assert.equal(squadrons[0].amount, 6)
+  })
+
+  it('Refitting a squadron at the shipyard', async () => {
+    await program.methods
+      .fleetRefit(
+        1,
+        3,
+        0,
+        constructShip(
+          padShipModules([
+            ['machineGun', 1],
+            ['machineGun', 1],
+            ['haulingBay', 1],
+          ]),
+        ),
+      )
+      .accounts({
+        signer: playerWallet.keypair.publicKey,
+      })
+      .signers([playerWallet.keypair])
+      .rpc()
+    const fleet = await program.account.fleet.fetch(fleetKey(1, 3))
+    assert.ok(fleet.squadrons[0].template.modules[2].moduleType.haulingBay)
+  })
+
+  it('Saving a ship design', async () => {
+    await program.methods
+      .shipDesignSave(
+        0,
+        'Gunboat',
+        constructShip(padShipModules([['machineGun', 1]])),
+      )
+      .accounts({
+        signer: playerWallet.keypair.publicKey,
+      })
+      .signers([playerWallet.keypair])
+      .rpc()
+    const design = await program.account.shipDesign.fetch(
+      shipDesignKey(playerWallet.keypair.publicKey, 0),
+    )
+    assert.equal(design.name, 'Gunboat')
+  })
+
+  it('Ship design without modules is rejected', async () => {
     try {
-      // Try to spawn another fleet on the planet
       await program.methods
-        .fleetNew(1, 3, createSimpleFleetTemplate())
+        .shipDesignSave(1, 'Empty', constructShip(padShipModules([])))
         .accounts({
           signer: playerWallet.keypair.publicKey,
         })
         .signers([playerWallet.keypair])
         .rpc()
-      return assert.fail('Failure')
+      return assert.fail('Empty ship design was saved')
+    } catch (e) {
+      assert.include(e.toString(), 'InvalidShipTemplate')
+    }
+  })
+
+  it('Ship design has to follow the rules of its hull', async () => {
+    try {
+      await program.methods
+        .shipDesignSave(
+          1,
+          'Armed hauler',
+          constructShip(
+            padShipModules([
+              ['machineGun', 1],
+              ['haulingBay', 1],
+            ]),
+            'corvette',
+          ),
+        )
+        .accounts({
+          signer: playerWallet.keypair.publicKey,
+        })
+        .signers([playerWallet.keypair])
+        .rpc()
+      return assert.fail('Corvette was saved with a hauling bay')
+    } catch (e) {
+      assert.include(e.toString(), 'ModuleNotAllowedOnHull')
+    }
+    try {
+      await program.methods
+        .shipDesignSave(
+          1,
+          'Overloaded',
+          constructShip(
+            padShipModules([
+              ['machineGun', 1],
+              ['machineGun', 1],
+              ['machineGun', 1],
+            ]),
+            'corvette',
+          ),
+        )
+        .accounts({
+          signer: playerWallet.keypair.publicKey,
+        })
+        .signers([playerWallet.keypair])
+        .rpc()
+      return assert.fail('Corvette was saved with three modules')
+    } catch (e) {
+      assert.include(e.toString(), 'HullSlotsExceeded')
+    }
+  })
+
+  it('Building a fleet from a saved design', async () => {
+    const squadrons = Array(9).fill(null)
+    squadrons[0] = { design: 0, amount: 1 }
+    await program.methods
+      .fleetNewFromDesigns(1, 3, squadrons)
+      .accounts({
+        signer: playerWallet.keypair.publicKey,
+      })
+      .remainingAccounts([
+        {
+          pubkey: shipDesignKey(playerWallet.keypair.publicKey, 0),
+          isSigner: false,
+          isWritable: false,
+        },
+      ])
+      .signers([playerWallet.keypair])
+      .rpc()
+    const fleet = await program.account.fleet.fetch(fleetKey(1, 3))
+    const gunboats = fleet.squadrons.find(
+      (s) =>
+        s &&
+        s.template.modules[0].moduleType.machineGun &&
+        s.template.modules[1].moduleType.none,
+    )
+    assert.equal(gunboats?.amount, 1)
+  })
+
+  it('Splitting ships off into an adjacent fleet', async () => {
+    await program.methods
+      .fleetSplit(1, 3, 1, 2, [2, 0, 0, 0, 0, 0, 0, 0, 0])
+      .accounts({
+        signer: playerWallet.keypair.publicKey,
+      })
+      .signers([playerWallet.keypair])
+      .rpc()
+    const fleet = await program.account.fleet.fetch(fleetKey(1, 3))
+    const splitFleet = await program.account.fleet.fetch(fleetKey(1, 2))
+    assert.equal(fleet.squadrons[0].amount, 4)
+    assert.equal(splitFleet.squadrons[0].amount, 2)
+  })
+
+  it('Fleet cannot be split to a position further away', async () => {
+    try {
+      await program.methods
+        .fleetSplit(1, 3, 1, 5, [1, 0, 0, 0, 0, 0, 0, 0, 0])
+        .accounts({
+          signer: playerWallet.keypair.publicKey,
+        })
+        .signers([playerWallet.keypair])
+        .rpc()
+      return assert.fail('Fleet was split to a distant position')
+    } catch (e) {
+      assert.include(e.toString(), 'NotAdjacent')
+    }
+  })
+
+  it('Fleet can only be disbanded at an owned planet', async () => {
+    try {
+      await program.methods
+        .fleetDisband(1, 2)
+        .accounts({
+          signer: playerWallet.keypair.publicKey,
+        })
+        .signers([playerWallet.keypair])
+        .rpc()
+      return assert.fail('Fleet was disbanded in empty space')
     } catch (e) {
       assert.ok('Ok')
     }
   })
 
+  it('Merging an adjacent fleet combines matching squadrons', async () => {
+    await program.methods
+      .fleetMerge(1, 3, 1, 2)
+      .accounts({
+        signer: playerWallet.keypair.publicKey,
+      })
+      .signers([playerWallet.keypair])
+      .rpc()
+    const fleet = await program.account.fleet.fetch(fleetKey(1, 3))
+    const mergedFleet = await program.account.fleet.fetch(fleetKey(1, 2))
+    assert.equal(fleet.squadrons[0].amount, 6)
+    assert.notOk(mergedFleet.isPresent)
+  })
+
   it("Fleet can't move where another fleet is present", async () => {
     try {
       await program.methods
@@ -1,13 +1,21 @@
 import * as anchor from '@coral-xyz/anchor'
 import { type Program } from '@coral-xyz/anchor'
 import { type SpaceCastle } from '../target/types/space_castle'
-import { PlayerInfo, getPlayerCache, usePlayer } from './utils/player'
+import {
+  PlayerInfo,
+  getPlayer,
+  getPlayerCache,
+  usePlayer,
+} from './utils/player'
 import { assert } from 'chai'
+import { getBuilding, getHoldings } from './utils/planet'
 import {
   fleetKey,
   fleetSufferedLosses,
   getFleet,
+  nextBattleReportKey,
   printFleet,
+  waitForArrival,
 } from './utils/fleet'
 
 describe('[Test]: ⚔️  Battle', () => {
@@ -17,6 +25,7 @@ describe('[Test]: ⚔️  Battle', () => {
   let fleet1: Awaited<ReturnType<typeof getFleet>>
   let fleet2: Awaited<ReturnType<typeof getFleet>>
   let secondPlayerWallet: PlayerInfo
+  let battleReport: anchor.web3.PublicKey
 
   anchor.setProvider(provider)
 
@@ -31,6 +40,27 @@ describe('[Test]: ⚔️  Battle', () => {
     //   provider,
     // )
   })
+  it('Fleet can only attack a position next to it', async () => {
+    try {
+      await program.methods
+        .fleetAttack(1, 6, 2, 6, 2, 5, false, null)
+        .accounts({
+          signer: playerWallet.keypair.publicKey,
+        })
+        .accountsPartial({
+          defender: secondPlayerWallet.keypair.publicKey,
+          battleReport: await nextBattleReportKey(
+            playerWallet.keypair.publicKey,
+            program,
+          ),
+        })
+        .signers([playerWallet.keypair])
+        .rpc()
+      return assert.fail('Attacked a position far away')
+    } catch (e) {
+      assert.include(e.toString(), 'NotAdjacent')
+    }
+  })
   it('Fleet can attack another fleet', async () => {
     // Move second player fleet off its planet
     await program.methods
@@ -44,21 +74,60 @@ describe('[Test]: ⚔️  Battle', () => {
       })
       .signers([secondPlayerWallet.keypair])
       .rpc()
+    // Bring the first player fleet next to it
+    await program.methods
+      .fleetMove(1, 3, 1, 6)
+      .accounts({
+        signer: playerWallet.keypair.publicKey,
+      })
+      .signers([playerWallet.keypair])
+      .rpc()
+    await waitForArrival(2, 6, program)
+    await program.methods
+      .fleetArrive(2, 6, 2, 7)
+      .accounts({
+        signer: secondPlayerWallet.keypair.publicKey,
+      })
+      .signers([secondPlayerWallet.keypair])
+      .rpc()
+    await waitForArrival(1, 3, program)
+    await program.methods
+      .fleetArrive(1, 3, 1, 6)
+      .accounts({
+        signer: playerWallet.keypair.publicKey,
+      })
+      .signers([playerWallet.keypair])
+      .rpc()
 
     // console.log('--------- BEFORE BATTLE ---------')
-    fleet1 = await getFleet(1, 3, program)
+    fleet1 = await getFleet(1, 6, program)
     fleet2 = await getFleet(2, 7, program)
     // printFleet(fleet1)
     // printFleet(fleet2)
     // First player attacks second fleet
+    battleReport = await nextBattleReportKey(
+      playerWallet.keypair.publicKey,
+      program,
+    )
     await program.methods
-      .fleetAttack(1, 3, 2, 7)
+      .fleetAttack(1, 6, 2, 7, 2, 8, false, null)
       .accounts({
         signer: playerWallet.keypair.publicKey,
       })
+      .accountsPartial({
+        defender: secondPlayerWallet.keypair.publicKey,
+        battleReport,
+      })
       .signers([playerWallet.keypair])
       .rpc()
   })
+  it('Battle report is stored', async () => {
+    const report = await program.account.battleReport.fetch(battleReport)
+    return report.attacker.equals(playerWallet.keypair.publicKey) &&
+      report.rounds.some((round) => round != null)
+      ? assert.ok('Report stored')
+      : assert.fail('Report is missing the battle')
+  })
   it('Winner of the battle is granted plunder', async () => {
     const player_cache = await getPlayerCache(
       playerWallet.keypair.publicKey,
@@ -78,7 +147,7 @@ describe('[Test]: ⚔️  Battle', () => {
     // console.log('--------- AFTER BATTLE ----------')
     const fleetBefore1 = { ...fleet1 }
     const fleetBefore2 = { ...fleet2 }
-    // fleet1 = await getFleet(1, 3, program)
+    // fleet1 = await getFleet(1, 6, program)
     // fleet2 = await getFleet(2, 7, program)
     // printFleet(fleet1)
     // printFleet(fleet2)
@@ -87,32 +156,255 @@ describe('[Test]: ⚔️  Battle', () => {
       fleetSufferedLosses(fleetBefore2, fleet2)
     )
   })
-  it('Fleet cant attack where there is not fleet', async () => {
+  it('Destroyed squadrons are removed and a beaten defender falls back', async () => {
+    const report = await program.account.battleReport.fetch(battleReport)
+    const attacker = await program.account.fleet.fetch(fleetKey(1, 6))
+    const target = await program.account.fleet.fetch(fleetKey(2, 7))
+    const retreat = await program.account.fleet.fetch(fleetKey(2, 8))
+    for (const fleet of [attacker, target, retreat]) {
+      assert.ok(fleet.squadrons.every((s) => s === null || s.amount > 0))
+    }
+    if (report.winner.attacker) {
+      assert.isFalse(target.isPresent)
+      assert.ok(
+        !retreat.isPresent ||
+          retreat.owner.equals(secondPlayerWallet.keypair.publicKey),
+      )
+    } else {
+      assert.ok(target.isPresent)
+    }
+  })
+  it('Battle is counted for both players', async () => {
+    const report = await program.account.battleReport.fetch(battleReport)
+    const attacker = await getPlayer(playerWallet.keypair.publicKey, program)
+    const defender = await getPlayer(
+      secondPlayerWallet.keypair.publicKey,
+      program,
+    )
+    if (report.winner.attacker) {
+      assert.equal(attacker.battlesWon, 1)
+      assert.equal(defender.battlesLost, 1)
+    } else {
+      assert.equal(attacker.battlesLost, 1)
+      assert.equal(defender.battlesWon, 1)
+    }
+  })
+  it('Battle report can be closed to recover its rent', async () => {
+    const report = await program.account.battleReport.fetch(battleReport)
+    await program.methods
+      .battleReportClose(report.id)
+      .accounts({
+        signer: playerWallet.keypair.publicKey,
+      })
+      .signers([playerWallet.keypair])
+      .rpc()
+    const closed = await program.account.battleReport.fetchNullable(
+      battleReport,
+    )
+    assert.isNull(closed)
+  })
+  it('Retreat has to be adjacent to the defender', async () => {
     try {
       await program.methods
-        .fleetAttack(1, 3, 1, 1)
+        .fleetAttack(1, 6, 2, 6, 4, 6, false, null)
         .accounts({
-          signer: secondPlayerWallet.keypair.publicKey,
+          signer: playerWallet.keypair.publicKey,
         })
-        .signers([secondPlayerWallet.keypair])
+        .accountsPartial({
+          defender: secondPlayerWallet.keypair.publicKey,
+          battleReport: await nextBattleReportKey(
+            playerWallet.keypair.publicKey,
+            program,
+          ),
+        })
+        .signers([playerWallet.keypair])
+        .rpc()
+      return assert.fail('Attacked with a retreat far from the defender')
+    } catch (e) {
+      assert.include(e.toString(), 'InvalidRetreat')
+    }
+  })
+  it('Debris can only be salvaged by a fleet next to it', async () => {
+    try {
+      await program.methods
+        .fleetSalvage(2, 3, 2, 7)
+        .accounts({
+          signer: playerWallet.keypair.publicKey,
+        })
+        .signers([playerWallet.keypair])
+        .rpc()
+      return assert.fail('Salvaged debris from afar')
+    } catch (e) {
+      assert.include(e.toString(), 'NotAdjacent')
+    }
+  })
+  it('Fleet cant attack where there is neither a fleet nor a planet', async () => {
+    try {
+      await program.methods
+        .fleetAttack(1, 6, 1, 7, 1, 8, false, null)
+        .accounts({
+          signer: playerWallet.keypair.publicKey,
+        })
+        .accountsPartial({
+          defender: secondPlayerWallet.keypair.publicKey,
+          battleReport: await nextBattleReportKey(
+            playerWallet.keypair.publicKey,
+            program,
+          ),
+        })
+        .signers([playerWallet.keypair])
         .rpc()
       return assert.fail('Somehow attacked where there is no fleet')
     } catch (e) {
-      return assert.ok('Ok')
+      assert.include(e.toString(), 'FleetNotPresent')
     }
   })
-  it('Fleet cant attack a planet as it would a fleet (that action is called planet invasion)', async () => {
+  it('Only the owner of a fleet can attack with it', async () => {
     try {
       await program.methods
-        .fleetAttack(1, 3, 2, 6)
+        .fleetAttack(1, 6, 2, 6, 2, 5, false, null)
         .accounts({
           signer: secondPlayerWallet.keypair.publicKey,
         })
+        .accountsPartial({
+          defender: playerWallet.keypair.publicKey,
+          battleReport: await nextBattleReportKey(
+            secondPlayerWallet.keypair.publicKey,
+            program,
+          ),
+        })
         .signers([secondPlayerWallet.keypair])
         .rpc()
-      return assert.fail('Failure')
+      return assert.fail('Attacked with a fleet of another player')
+    } catch (e) {
+      assert.include(e.toString(), 'NoAuthority')
+    }
+  })
+  it('Planet without a fleet can be attacked and plundered', async () => {
+    const defender = secondPlayerWallet.keypair.publicKey
+    const cacheBefore = await getPlayerCache(
+      playerWallet.keypair.publicKey,
+      program,
+    )
+    const holdingBefore = await getHoldings(2, 6, defender, program)
+    await program.methods
+      .fleetAttack(1, 6, 2, 6, 2, 5, false, null)
+      .accounts({
+        signer: playerWallet.keypair.publicKey,
+      })
+      .accountsPartial({
+        defender,
+        battleReport: await nextBattleReportKey(
+          playerWallet.keypair.publicKey,
+          program,
+        ),
+      })
+      .signers([playerWallet.keypair])
+      .rpc()
+    const cacheAfter = await getPlayerCache(
+      playerWallet.keypair.publicKey,
+      program,
+    )
+    const holdingAfter = await getHoldings(2, 6, defender, program)
+    // Nobody lost ships, so the attacker's cache only grew by the plunder
+    for (const resource of ['metal', 'crystal', 'chemical', 'fuel'] as const) {
+      assert.equal(
+        cacheAfter.resources[resource]
+          .sub(cacheBefore.resources[resource])
+          .toString(),
+        holdingAfter.plundered[resource]
+          .sub(holdingBefore.plundered[resource])
+          .toString(),
+      )
+    }
+  })
+  it('Planet without a fleet can be bombarded', async () => {
+    const defender = secondPlayerWallet.keypair.publicKey
+    const before = getBuilding(
+      await getHoldings(2, 6, defender, program),
+      'shipyard',
+    )
+    await program.methods
+      .fleetAttack(1, 6, 2, 6, 2, 5, false, { shipyard: {} })
+      .accounts({
+        signer: playerWallet.keypair.publicKey,
+      })
+      .accountsPartial({
+        defender,
+        battleReport: await nextBattleReportKey(
+          playerWallet.keypair.publicKey,
+          program,
+        ),
+      })
+      .signers([playerWallet.keypair])
+      .rpc()
+    const after = getBuilding(
+      await getHoldings(2, 6, defender, program),
+      'shipyard',
+    )
+    // Levels knocked off can be repaired later
+    assert.equal(
+      after.level + after.damagedLevels,
+      before.level + before.damagedLevels,
+    )
+  })
+  it('Planet cannot be invaded by a fleet without landing pods', async () => {
+    try {
+      await program.methods
+        .planetInvade(1, 6, 2, 6, 1, 3, 2, 5)
+        .accounts({
+          signer: playerWallet.keypair.publicKey,
+        })
+        .accountsPartial({
+          previousOwner: secondPlayerWallet.keypair.publicKey,
+          defender: secondPlayerWallet.keypair.publicKey,
+          battleReport: await nextBattleReportKey(
+            playerWallet.keypair.publicKey,
+            program,
+          ),
+        })
+        .signers([playerWallet.keypair])
+        .rpc()
+      return assert.fail('Invaded a planet without landing pods')
+    } catch (e) {
+      assert.include(e.toString(), 'NotEnoughLandingPods')
+    }
+  })
+  it('Fleet with hauling bays salvages the debris next to it', async () => {
+    // The fleet attacked from next to the debris of the first battle at (2, 7)
+    const cacheBefore = await getPlayerCache(
+      playerWallet.keypair.publicKey,
+      program,
+    )
+    await program.methods
+      .fleetSalvage(1, 6, 2, 7)
+      .accounts({
+        signer: playerWallet.keypair.publicKey,
+      })
+      .signers([playerWallet.keypair])
+      .rpc()
+    const cacheAfter = await getPlayerCache(
+      playerWallet.keypair.publicKey,
+      program,
+    )
+    assert.ok(
+      (['metal', 'crystal', 'chemical', 'fuel'] as const).some((resource) =>
+        cacheAfter.resources[resource].gt(cacheBefore.resources[resource]),
+      ),
+    )
+  })
+  it('Fleet has to wait before it salvages again', async () => {
+    try {
+      await program.methods
+        .fleetSalvage(1, 6, 2, 7)
+        .accounts({
+          signer: playerWallet.keypair.publicKey,
+        })
+        .signers([playerWallet.keypair])
+        .rpc()
+      return assert.fail('Salvaged twice in a row')
     } catch (e) {
-      return assert.ok('Ok')
+      assert.include(e.toString(), 'SalvageOnCooldown')
     }
   })
 })
@@ -2,6 +2,8 @@ import { type Program } from '@coral-xyz/anchor'
 import { SpaceCastle } from '../../target/types/space_castle'
 import * as anchor from '@coral-xyz/anchor'
 import { PublicKey } from '@solana/web3.js'
+import { waitForSlots } from './provider'
+import { getPlayer } from './player'
 
 const program = anchor.workspace.SpaceCastle as Program<SpaceCastle>
 
@@ -20,8 +22,12 @@ export type FleetBlueprint = Parameters<typeof program.methods.fleetNew>[2]
 export type SquadronBlueprint = Exclude<FleetBlueprint[0], null>
 /// Template for a ship
 export type ShipType = SquadronBlueprint['template']
+/// Hull of a ship
+export type HullType = ShipType['hull']
+/// Keys of hull classes
+export type HullName = keyof HullType
 /// Module in a ship
-export type ShipModuleType = ShipType[0]
+export type ShipModuleType = ShipType['modules'][0]
 /// Keys of modules in a ship
 export type ShipModuleName = keyof ShipModuleType['moduleType']
 
@@ -44,13 +50,13 @@ export const padShipModules = (shipModules: Array<[ShipModuleName, number]>) =>
   )
 
 export const constructFleet = (
-  squadrons: [[ShipModuleName, number][], number][],
+  squadrons: [[ShipModuleName, number][], number, HullName?][],
 ) => {
   const full_squadrons = squadrons.map(
-    ([ship, amount]) =>
+    ([ship, amount, hull]) =>
       ({
         amount,
-        template: constructShip(ship),
+        template: constructShip(ship, hull),
       }) as SquadronBlueprint,
   ) as FleetBlueprint
   const padded_squadrons = full_squadrons.concat(
@@ -59,8 +65,18 @@ export const constructFleet = (
   return padded_squadrons
 }
 
-export const constructShip = (modules: [ShipModuleName, number][]) => {
-  return modules.map(([module, level]) => constructShipModule(module, level))
+export const constructShip = (
+  modules: [ShipModuleName, number][],
+  hull: HullName = 'frigate',
+) => {
+  const hullType = {}
+  hullType[hull] = {}
+  return {
+    hull: hullType as HullType,
+    modules: modules.map(([module, level]) =>
+      constructShipModule(module, level),
+    ),
+  }
 }
 
 export const constructShipModule = (type: ShipModuleName, level: number) => {
@@ -102,7 +118,7 @@ export const printFleet = (fleet: Fleet) => {
   console.log(`  Composition: `)
   fleet.squadrons.forEach((squadron, index) => {
     if (squadron) {
-      const modules = squadron.template.reduce(
+      const modules = squadron.template.modules.reduce(
         (modules, module) =>
           module.moduleType.none
             ? modules
@@ -139,3 +155,46 @@ export const fleetKey = (x: number, y: number) => {
   )
   return fleet
 }
+
+/// Waits until the fleet travelling from (x, y) reaches its destination
+export async function waitForArrival(
+  x: number,
+  y: number,
+  program: anchor.Program<SpaceCastle>,
+) {
+  const fleet = await program.account.fleet.fetch(fleetKey(x, y))
+  const connection = program.provider.connection
+  const arrivalSlot = fleet.transit?.arrivalSlot.toNumber() ?? 0
+  await waitForSlots(connection, arrivalSlot - (await connection.getSlot()))
+}
+
+export const shipDesignKey = (owner: PublicKey, index: number) => {
+  const indexBuffer = Buffer.alloc(2)
+  indexBuffer.writeUInt16LE(index, 0)
+  const [design] = PublicKey.findProgramAddressSync(
+    [Buffer.from('ship_design'), owner.toBuffer(), indexBuffer],
+    program.programId,
+  )
+  return design
+}
+
+export const battleReportKey = (attacker: PublicKey, id: anchor.BN) => {
+  const [battleReport] = PublicKey.findProgramAddressSync(
+    [
+      Buffer.from('battle_report'),
+      attacker.toBuffer(),
+      id.toArrayLike(Buffer, 'le', 8),
+    ],
+    program.programId,
+  )
+  return battleReport
+}
+
+/// Key of the report that the attacker's next battle will be stored in
+export async function nextBattleReportKey(
+  attacker: PublicKey,
+  program: anchor.Program<SpaceCastle>,
+) {
+  const player = await getPlayer(attacker, program)
+  return battleReportKey(attacker, player.battleReports)
+}
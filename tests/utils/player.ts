@@ -172,3 +172,14 @@ export async function getPlayerCache(
   const player_cache = await program.account.playerCache.fetch(pda)
   return player_cache
 }
+
+export async function getPlayer(
+  publicKey: PublicKey,
+  program: anchor.Program<SpaceCastle>,
+) {
+  const [pda] = PublicKey.findProgramAddressSync(
+    [Buffer.from('player'), publicKey.toBuffer()],
+    program.programId,
+  )
+  return program.account.player.fetch(pda)
+}
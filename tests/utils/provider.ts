@@ -1,3 +1,18 @@
+import * as anchor from '@coral-xyz/anchor'
+
 export function isDevnet() {
   return true
 }
+
+// Planet production is counted in ticks of 150 slots
+export const PRODUCTION_TICK_SLOTS = 150
+
+export async function waitForSlots(
+  connection: anchor.web3.Connection,
+  slots: number,
+) {
+  const target = (await connection.getSlot()) + slots
+  while ((await connection.getSlot()) < target) {
+    await new Promise((resolve) => setTimeout(resolve, 400))
+  }
+}
@@ -31,6 +31,8 @@ export function fromBigIntQuantity(quantity: bigint, decimals: number): string {
 
 export async function mintAllResourcesToAddress(signer: Signer) {
   const program = anchor.workspace.SpaceCastle as anchor.Program<SpaceCastle>
+  // Game admin is the provider wallet, it signs the minting
+  const admin = anchor.AnchorProvider.env().wallet.publicKey
   const methods = {
     metal: program.methods.mintMetal,
     crystal: program.methods.mintCrystal,
@@ -52,6 +54,7 @@ export async function mintAllResourcesToAddress(signer: Signer) {
         .accounts({
           tokenAccount: associatedTokenAccount,
           payer: signer.publicKey,
+          admin,
         })
         .signers([signer])
         .rpc()
@@ -59,6 +62,7 @@ export async function mintAllResourcesToAddress(signer: Signer) {
       await methods[resource.mintKey](new anchor.BN(1000000))
         .accounts({
           payer: signer.publicKey,
+          admin,
         })
         .signers([signer])
         .rpc()